@@ -0,0 +1,137 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+//! Credentials attached to module downloads from private registries.
+//!
+//! They are read from the `DENO_AUTH_TOKENS` environment variable, a `;`
+//! separated list of `token@host` (bearer) or `username:password@host`
+//! (basic) entries, e.g. `abc123@deno.land;me:secret@localhost:8080`.
+use std::fmt;
+use url::Url;
+
+#[derive(Clone, PartialEq)]
+enum AuthTokenData {
+  Bearer(String),
+  Basic { username: String, password: String },
+}
+
+#[derive(Clone, PartialEq)]
+pub struct AuthToken {
+  /// Host, and port if one was given, the token is sent to.
+  host: String,
+  token: AuthTokenData,
+}
+
+impl AuthToken {
+  /// Value of the `Authorization` header.
+  pub fn header_value(&self) -> String {
+    match &self.token {
+      AuthTokenData::Bearer(token) => format!("Bearer {}", token),
+      AuthTokenData::Basic { username, password } => format!(
+        "Basic {}",
+        base64::encode(&format!("{}:{}", username, password))
+      ),
+    }
+  }
+}
+
+// Tokens must not end up in logs, only the host is shown.
+impl fmt::Debug for AuthToken {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "AuthToken {{ host: {:?}, token: <redacted> }}",
+      self.host
+    )
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuthTokens(Vec<AuthToken>);
+
+impl AuthTokens {
+  /// Parses the value of `DENO_AUTH_TOKENS`, malformed entries are ignored.
+  pub fn new(maybe_tokens_str: Option<String>) -> Self {
+    let mut tokens = vec![];
+    if let Some(tokens_str) = maybe_tokens_str {
+      for token_str in tokens_str.split(';') {
+        // The host can't contain '@', but the token can.
+        let (token, host) = match token_str.rfind('@') {
+          Some(i) => (&token_str[..i], &token_str[i + 1..]),
+          None => {
+            error!("Badly formed auth token discarded.");
+            continue;
+          }
+        };
+        if token.is_empty() || host.is_empty() {
+          error!("Badly formed auth token discarded.");
+          continue;
+        }
+        let token = match token.find(':') {
+          Some(i) => AuthTokenData::Basic {
+            username: token[..i].to_string(),
+            password: token[i + 1..].to_string(),
+          },
+          None => AuthTokenData::Bearer(token.to_string()),
+        };
+        tokens.push(AuthToken {
+          host: host.to_lowercase(),
+          token,
+        });
+      }
+    }
+    AuthTokens(tokens)
+  }
+
+  /// Returns the token for the host of `url`. A token for a host without a
+  /// port is used for any port.
+  pub fn get(&self, url: &Url) -> Option<&AuthToken> {
+    let host = url.host_str()?;
+    let host_port = url.port().map(|port| format!("{}:{}", host, port));
+    self.0.iter().find(|token| {
+      token.host == host || Some(&token.host) == host_port.as_ref()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_auth_token_bearer() {
+    let auth_tokens = AuthTokens::new(Some("abc123@deno.land".to_string()));
+    let url = Url::parse("https://deno.land/x/mod.ts").unwrap();
+    assert_eq!(
+      auth_tokens.get(&url).unwrap().header_value(),
+      "Bearer abc123"
+    );
+    let url = Url::parse("https://example.com/x/mod.ts").unwrap();
+    assert!(auth_tokens.get(&url).is_none());
+  }
+
+  #[test]
+  fn test_auth_token_basic() {
+    let auth_tokens =
+      AuthTokens::new(Some("abc123@deno.land;me:p@ss@localhost:8080".into()));
+    let url = Url::parse("http://localhost:8080/x/mod.ts").unwrap();
+    assert_eq!(
+      auth_tokens.get(&url).unwrap().header_value(),
+      format!("Basic {}", base64::encode("me:p@ss"))
+    );
+    // Only sent to the given port.
+    let url = Url::parse("http://localhost:4545/x/mod.ts").unwrap();
+    assert!(auth_tokens.get(&url).is_none());
+  }
+
+  #[test]
+  fn test_auth_token_malformed() {
+    let auth_tokens =
+      AuthTokens::new(Some("abc123;@deno.land;def456@".to_string()));
+    assert_eq!(auth_tokens, AuthTokens::default());
+  }
+
+  #[test]
+  fn test_auth_token_debug_redacted() {
+    let auth_tokens = AuthTokens::new(Some("abc123@deno.land".to_string()));
+    assert!(!format!("{:?}", auth_tokens).contains("abc123"));
+  }
+}
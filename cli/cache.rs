@@ -0,0 +1,312 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+//! Implementation of `deno cache`, which manages the remote module cache
+//! (`$DENO_DIR/deps`) and the compiler cache (`$DENO_DIR/gen`).
+use crate::colors;
+use crate::compilers::source_code_version_hash;
+use crate::compilers::CompiledFileMetadata;
+use crate::disk_cache::DiskCache;
+use crate::file_fetcher::SourceCodeHeaders;
+use crate::global_state::ThreadSafeGlobalState;
+use crate::version;
+use deno::ErrBox;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use url::Url;
+
+static HEADERS_SUFFIX: &str = ".headers.json";
+
+/// Extensions of the files `TsCompiler` writes for each module.
+static GEN_SUFFIXES: [&str; 3] = [".js.map", ".js", ".meta"];
+
+/// Prints the remote modules in the cache with their size and the time they
+/// were fetched, as well as the redirects that lead to them.
+pub fn list(global_state: &ThreadSafeGlobalState) -> Result<(), ErrBox> {
+  let deps_cache = &global_state.dir.deps_cache;
+  let mut count = 0;
+  let mut total_size = 0;
+
+  for filename in deps_cache.list()? {
+    if let Some(source_filename) = strip_suffix(&filename, HEADERS_SUFFIX) {
+      let headers = SourceCodeHeaders::from_json_string(
+        String::from_utf8_lossy(&deps_cache.get(&filename)?).into_owned(),
+      );
+      if let (Some(url), Some(redirect_to)) =
+        (deps_cache.get_url(&source_filename), headers.redirect_to)
+      {
+        println!("{:>10}  {:19}  {} -> {}", "", "", url, redirect_to);
+      }
+      continue;
+    }
+    let url = match deps_cache.get_url(&filename) {
+      Some(url) => url,
+      None => continue,
+    };
+    let size = fs::metadata(deps_cache.location.join(&filename))?.len();
+    println!(
+      "{:>10}  {}  {}",
+      format_size(size),
+      format_time(fetch_time(deps_cache, &url)?),
+      url
+    );
+    count += 1;
+    total_size += size;
+  }
+
+  println!(
+    "{} {} modules, {}",
+    colors::bold("Total:".to_string()),
+    count,
+    format_size(total_size)
+  );
+  Ok(())
+}
+
+/// Checks every compiled module in the cache against the source it was
+/// compiled from. Returns false if any of them is stale or incomplete.
+pub fn verify(global_state: &ThreadSafeGlobalState) -> Result<bool, ErrBox> {
+  let gen_cache = &global_state.dir.gen_cache;
+  let config_hash = &global_state.ts_compiler.config.hash;
+  let mut count = 0;
+  let mut failed = 0;
+
+  for filename in gen_cache.list()? {
+    if strip_suffix(&filename, ".meta").is_none() {
+      continue;
+    }
+    count += 1;
+    let metadata = CompiledFileMetadata::from_json_string(
+      String::from_utf8_lossy(&gen_cache.get(&filename)?).into_owned(),
+    );
+    let (source_path, problem) = match metadata {
+      None => (filename.clone(), Some("malformed metadata")),
+      Some(metadata) => {
+        let problem = match fs::read(&metadata.source_path) {
+          Err(_) => Some("source missing"),
+          Ok(source_code) => {
+            let version_hash = source_code_version_hash(
+              &source_code,
+              version::DENO,
+              config_hash,
+            );
+            if version_hash != metadata.version_hash {
+              Some("stale")
+            } else if !gen_cache
+              .location
+              .join(filename.with_extension("js"))
+              .is_file()
+            {
+              Some("output missing")
+            } else {
+              None
+            }
+          }
+        };
+        (metadata.source_path, problem)
+      }
+    };
+    if let Some(problem) = problem {
+      failed += 1;
+      println!(
+        "{} {}",
+        colors::red(format!("{}:", problem)),
+        source_path.display()
+      );
+    }
+  }
+
+  println!(
+    "{} {} compiled modules, {} failed",
+    colors::bold("Verified:".to_string()),
+    count,
+    failed
+  );
+  Ok(failed == 0)
+}
+
+/// Removes cached files that belong to neither the given modules nor the
+/// redirects leading to them.
+pub fn prune(
+  global_state: &ThreadSafeGlobalState,
+  modules: &[Url],
+) -> Result<(), ErrBox> {
+  let deps_cache = &global_state.dir.deps_cache;
+  let gen_cache = &global_state.dir.gen_cache;
+
+  let mut keep_deps = HashSet::new();
+  let mut keep_gen = HashSet::new();
+  for module_url in modules {
+    let mut urls = vec![module_url.clone()];
+    urls.extend(
+      global_state
+        .file_fetcher
+        .get_redirect_chain(module_url)
+        .into_iter()
+        .map(|(_from, to)| to),
+    );
+    for url in urls {
+      if url.scheme() == "http" || url.scheme() == "https" {
+        keep_deps.insert(deps_cache.get_cache_filename(&url));
+        keep_deps.insert(
+          deps_cache.get_cache_filename_with_extension(&url, "headers.json"),
+        );
+      }
+      keep_gen.insert(gen_cache.get_cache_filename(&url));
+    }
+  }
+
+  let (deps_count, deps_size) =
+    remove_files(deps_cache, |filename| !keep_deps.contains(filename))?;
+  let (gen_count, gen_size) = remove_files(gen_cache, |filename| {
+    GEN_SUFFIXES
+      .iter()
+      .filter_map(|suffix| strip_suffix(filename, suffix))
+      .next()
+      .map_or(false, |base| !keep_gen.contains(&base))
+  })?;
+
+  println!(
+    "{} {} files, {}",
+    colors::bold("Removed:".to_string()),
+    deps_count + gen_count,
+    format_size(deps_size + gen_size)
+  );
+  Ok(())
+}
+
+/// Removes the remote module and compiler caches.
+pub fn clear(global_state: &ThreadSafeGlobalState) -> Result<(), ErrBox> {
+  global_state.dir.deps_cache.clear()?;
+  global_state.dir.gen_cache.clear()?;
+  println!(
+    "{} {:?}",
+    colors::bold("Cleared:".to_string()),
+    global_state.dir.root
+  );
+  Ok(())
+}
+
+/// Removes the files for which `predicate` returns true, returning their
+/// number and total size.
+fn remove_files<P>(
+  cache: &DiskCache,
+  predicate: P,
+) -> Result<(u64, u64), ErrBox>
+where
+  P: Fn(&PathBuf) -> bool,
+{
+  let mut count = 0;
+  let mut size = 0;
+  for filename in cache.list()? {
+    if predicate(&filename) {
+      size += fs::metadata(cache.location.join(&filename))?.len();
+      cache.remove(&filename)?;
+      count += 1;
+    }
+  }
+  Ok((count, size))
+}
+
+/// `.headers.json` is rewritten on every download, but it is only created if
+/// there is something to record. Otherwise the source file itself is used.
+fn fetch_time(deps_cache: &DiskCache, url: &Url) -> Result<SystemTime, ErrBox> {
+  let headers_path = deps_cache
+    .location
+    .join(deps_cache.get_cache_filename_with_extension(url, "headers.json"));
+  let source_path =
+    deps_cache.location.join(deps_cache.get_cache_filename(url));
+  let time = fs::metadata(headers_path)
+    .and_then(|metadata| metadata.modified())
+    .or_else(|_| fs::metadata(source_path)?.modified())?;
+  Ok(time)
+}
+
+fn strip_suffix(filename: &Path, suffix: &str) -> Option<PathBuf> {
+  let filename = filename.to_str()?;
+  if filename.ends_with(suffix) && filename.len() > suffix.len() {
+    Some(PathBuf::from(&filename[..filename.len() - suffix.len()]))
+  } else {
+    None
+  }
+}
+
+fn format_size(size: u64) -> String {
+  const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+  let mut value = size as f64;
+  let mut unit = 0;
+  while value >= 1024.0 && unit < UNITS.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+  if unit == 0 {
+    format!("{}{}", size, UNITS[0])
+  } else {
+    format!("{:.1}{}", value, UNITS[unit])
+  }
+}
+
+/// Formats the time as "YYYY-MM-DD HH:MM:SS" in UTC.
+fn format_time(time: SystemTime) -> String {
+  let secs = time
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs() as i64)
+    .unwrap_or(0);
+  let (days, secs_of_day) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+  // Converts days since the epoch to a civil date, see
+  // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z.rem_euclid(146_097);
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+  format!(
+    "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+    year,
+    month,
+    day,
+    secs_of_day / 3600,
+    secs_of_day % 3600 / 60,
+    secs_of_day % 60
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  #[test]
+  fn test_format_size() {
+    assert_eq!(format_size(0), "0B");
+    assert_eq!(format_size(1023), "1023B");
+    assert_eq!(format_size(1536), "1.5KB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0MB");
+  }
+
+  #[test]
+  fn test_format_time() {
+    assert_eq!(format_time(UNIX_EPOCH), "1970-01-01 00:00:00");
+    let time = UNIX_EPOCH + Duration::from_secs(1_582_979_696);
+    assert_eq!(format_time(time), "2020-02-29 12:34:56");
+  }
+
+  #[test]
+  fn test_strip_suffix() {
+    assert_eq!(
+      strip_suffix(Path::new("https/deno.land/mod.ts.js.map"), ".js.map"),
+      Some(PathBuf::from("https/deno.land/mod.ts"))
+    );
+    assert_eq!(
+      strip_suffix(Path::new("https/deno.land/mod.ts"), ".js"),
+      None
+    );
+  }
+}
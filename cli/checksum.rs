@@ -18,3 +18,39 @@ pub fn gen(v: Vec<&[u8]>) -> String {
 pub fn gen2(s: &str) -> String {
   gen(vec![s.as_bytes()])
 }
+
+/// Checks `bytes` against Subresource Integrity metadata such as
+/// "sha384-<base64 digest>". The metadata may list several space separated
+/// hashes, in which case any one of them matching is sufficient.
+pub fn check_integrity(integrity: &str, bytes: &[u8]) -> bool {
+  integrity.split_whitespace().any(|metadata| {
+    let mut parts = metadata.splitn(2, '-');
+    let algorithm = match parts.next().unwrap() {
+      "sha256" => &ring::digest::SHA256,
+      "sha384" => &ring::digest::SHA384,
+      "sha512" => &ring::digest::SHA512,
+      _ => return false,
+    };
+    let expected = match parts.next().map(base64::decode) {
+      Some(Ok(expected)) => expected,
+      _ => return false,
+    };
+    ring::digest::digest(algorithm, bytes).as_ref() == expected.as_slice()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_check_integrity() {
+    let empty_sha256 = "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=";
+    assert!(check_integrity(empty_sha256, b""));
+    assert!(!check_integrity(empty_sha256, b"console.log(1)"));
+    assert!(check_integrity(&format!("sha512-abc {}", empty_sha256), b""));
+    assert!(!check_integrity("md5-1B2M2Y8AsgTpgAmY7PhCfg==", b""));
+    assert!(!check_integrity("sha256-not base64", b""));
+    assert!(!check_integrity("", b""));
+  }
+}
@@ -9,6 +9,8 @@ mod wasm;
 
 pub use js::JsCompiler;
 pub use json::JsonCompiler;
+pub use ts::source_code_version_hash;
+pub use ts::CompiledFileMetadata;
 pub use ts::TsCompiler;
 pub use wasm::WasmCompiler;
 
@@ -296,6 +296,13 @@ impl TsCompiler {
     c.insert(url.clone());
   }
 
+  /// Makes the module compile again the next time it is requested, for
+  /// example because its source changed.
+  pub fn unmark_compiled(&self, url: &Url) {
+    let mut c = self.compiled.lock().unwrap();
+    c.remove(url);
+  }
+
   /// Check if given module URL has already been compiled and can be fetched directly from disk.
   fn has_compiled(&self, url: &Url) -> bool {
     let c = self.compiled.lock().unwrap();
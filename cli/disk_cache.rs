@@ -117,6 +117,62 @@ impl DiskCache {
     let path = self.location.join(filename);
     fs::remove_file(path)
   }
+
+  /// Returns the filenames of all files in the cache, relative to its
+  /// location. A cache that was never written to is empty.
+  pub fn list(self: &Self) -> std::io::Result<Vec<PathBuf>> {
+    let mut out = vec![];
+    if self.location.is_dir() {
+      list_dir(&self.location, &PathBuf::new(), &mut out)?;
+    }
+    out.sort();
+    Ok(out)
+  }
+
+  /// Removes every file in the cache.
+  pub fn clear(self: &Self) -> std::io::Result<()> {
+    match fs::remove_dir_all(&self.location) {
+      Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+      result => result,
+    }
+  }
+
+  /// Inverse of `get_cache_filename` for remote modules. Returns `None` for
+  /// local files and filenames that could not have been produced from a URL.
+  pub fn get_url(self: &Self, filename: &Path) -> Option<Url> {
+    let mut components = filename
+      .components()
+      .map(|c| c.as_os_str().to_str().map(String::from));
+    let scheme = components.next()??;
+    if scheme != "http" && scheme != "https" {
+      return None;
+    }
+    let host_port = components.next()??;
+    let host = match host_port.rfind("_PORT") {
+      Some(i) => format!("{}:{}", &host_port[..i], &host_port[i + 5..]),
+      None => host_port,
+    };
+    let segments: Option<Vec<String>> = components.collect();
+    let url = format!("{}://{}/{}", scheme, host, segments?.join("/"));
+    Url::parse(&url).ok()
+  }
+}
+
+fn list_dir(
+  root: &Path,
+  prefix: &Path,
+  out: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+  for entry in fs::read_dir(root.join(prefix))? {
+    let entry = entry?;
+    let filename = prefix.join(entry.file_name());
+    if entry.file_type()?.is_dir() {
+      list_dir(root, &filename, out)?;
+    } else {
+      out.push(filename);
+    }
+  }
+  Ok(())
 }
 
 #[cfg(test)]
@@ -205,4 +261,44 @@ mod tests {
       )
     }
   }
+
+  #[test]
+  fn test_get_url() {
+    let cache = DiskCache::new(&PathBuf::from("foo"));
+
+    for url in &[
+      "http://deno.land/std/http/file_server.ts",
+      "http://localhost:8000/std/http/file_server.ts",
+      "https://deno.land/x/mod.ts",
+    ] {
+      let url = Url::parse(url).unwrap();
+      let filename = cache.get_cache_filename(&url);
+      assert_eq!(cache.get_url(&filename), Some(url));
+    }
+
+    assert_eq!(cache.get_url(&PathBuf::from("file/std/mod.ts")), None);
+  }
+
+  #[test]
+  fn test_list_and_clear() {
+    let temp_dir = tempfile::TempDir::new().expect("tempdir fail");
+    let cache = DiskCache::new(&temp_dir.path().join("deps"));
+    assert!(cache.list().unwrap().is_empty());
+
+    cache.set(Path::new("https/deno.land/b.ts"), b"b").unwrap();
+    cache
+      .set(Path::new("https/deno.land/a/a.ts"), b"a")
+      .unwrap();
+    assert_eq!(
+      cache.list().unwrap(),
+      vec![
+        PathBuf::from("https/deno.land/a/a.ts"),
+        PathBuf::from("https/deno.land/b.ts"),
+      ]
+    );
+
+    cache.clear().unwrap();
+    assert!(cache.list().unwrap().is_empty());
+    cache.clear().unwrap();
+  }
 }
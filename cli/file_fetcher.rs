@@ -1,4 +1,6 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use crate::auth_tokens::AuthTokens;
+use crate::checksum;
 use crate::deno_error::too_many_redirects;
 use crate::deno_error::DenoError;
 use crate::deno_error::ErrorKind;
@@ -6,6 +8,8 @@ use crate::deno_error::GetErrorKind;
 use crate::disk_cache::DiskCache;
 use crate::http_util;
 use crate::http_util::FetchOnceResult;
+use crate::http_util::Validators;
+use crate::import_map::IntegrityMap;
 use crate::msg;
 use crate::progress::Progress;
 use deno::ErrBox;
@@ -13,6 +17,7 @@ use deno::ModuleSpecifier;
 use futures::future::Either;
 use futures::future::FutureExt;
 use futures::future::TryFutureExt;
+use reqwest::r#async::Client;
 use serde_json;
 use std;
 use std::collections::HashMap;
@@ -61,6 +66,11 @@ impl SourceFileCache {
       None => None,
     }
   }
+
+  pub fn remove(&self, key: &str) {
+    let mut c = self.0.lock().unwrap();
+    c.remove(key);
+  }
 }
 
 const SUPPORTED_URL_SCHEMES: [&str; 3] = ["http", "https", "file"];
@@ -76,6 +86,11 @@ pub struct SourceFileFetcher {
   use_disk_cache: bool,
   no_remote: bool,
   cached_only: bool,
+  /// Integrity metadata from the import map, keyed by module URL.
+  integrity: Arc<IntegrityMap>,
+  /// Credentials sent with downloads from private hosts.
+  auth_tokens: AuthTokens,
+  http_client: Client,
 }
 
 impl SourceFileFetcher {
@@ -86,6 +101,9 @@ impl SourceFileFetcher {
     cache_blacklist: Vec<String>,
     no_remote: bool,
     cached_only: bool,
+    integrity: IntegrityMap,
+    auth_tokens: AuthTokens,
+    http_client: Client,
   ) -> std::io::Result<Self> {
     let file_fetcher = Self {
       deps_cache,
@@ -95,6 +113,9 @@ impl SourceFileFetcher {
       use_disk_cache,
       no_remote,
       cached_only,
+      integrity: Arc::new(integrity),
+      auth_tokens,
+      http_client,
     };
 
     Ok(file_fetcher)
@@ -113,6 +134,36 @@ impl SourceFileFetcher {
     Ok(())
   }
 
+  /// Verifies the source file against integrity metadata pinned for
+  /// `module_url` in the import map, if any.
+  fn check_integrity(
+    self: &Self,
+    module_url: &Url,
+    source_file: &SourceFile,
+  ) -> Result<(), ErrBox> {
+    if let Some(integrity) = self.integrity.get(module_url.as_str()) {
+      if !checksum::check_integrity(integrity, &source_file.source_code) {
+        return Err(
+          DenoError::new(
+            ErrorKind::IntegrityCheckFailed,
+            format!(
+              "Integrity check failed for module \"{}\", expected \"{}\"",
+              module_url, integrity
+            ),
+          )
+          .into(),
+        );
+      }
+    }
+    Ok(())
+  }
+
+  /// Forgets the in-memory copy of the file, so that it is read again the next
+  /// time it is fetched.
+  pub fn evict_cached_source_file(&self, specifier: &ModuleSpecifier) {
+    self.source_file_cache.remove(&specifier.to_string());
+  }
+
   /// Required for TS compiler and source maps.
   pub fn fetch_cached_source_file(
     self: &Self,
@@ -370,7 +421,10 @@ impl SourceFileFetcher {
     if use_disk_cache && !is_blacklisted {
       match self.fetch_cached_remote_source(&module_url) {
         Ok(Some(source_file)) => {
-          return futures::future::ok(source_file).boxed();
+          let result = self
+            .check_integrity(&module_url, &source_file)
+            .map(|()| source_file);
+          return futures::future::ready(result).boxed();
         }
         Ok(None) => {
           // there's no cached version
@@ -397,13 +451,44 @@ impl SourceFileFetcher {
       .boxed();
     }
 
+    // A copy that is already cached is revalidated rather than downloaded
+    // again.
+    let cached = self.get_cached_validators(&module_url);
     let download_job = self.progress.add("Download", &module_url.to_string());
     let dir = self.clone();
     let module_url = module_url.clone();
 
-    // Single pass fetch, either yields code or yields redirect.
-    let f = http_util::fetch_string_once(&module_url).and_then(move |r| {
+    // Single pass fetch, either yields code, redirect or confirms the cache.
+    let f = http_util::fetch_string_once(
+      &self.http_client,
+      &module_url,
+      &cached,
+      &self.auth_tokens,
+    )
+    .and_then(move |r| {
       match r {
+        FetchOnceResult::NotModified => {
+          // Explicit drop to keep reference alive until future completes.
+          drop(download_job);
+
+          let result = dir
+            .fetch_cached_remote_source(&module_url)
+            .and_then(|maybe_source_file| {
+              maybe_source_file.ok_or_else(|| {
+                std::io::Error::new(
+                  std::io::ErrorKind::NotFound,
+                  format!("cannot find remote file '{}' in cache", module_url),
+                )
+                .into()
+              })
+            })
+            .and_then(|source_file| {
+              dir
+                .check_integrity(&module_url, &source_file)
+                .map(|()| source_file)
+            });
+          Either::Right(futures::future::ready(result))
+        }
         FetchOnceResult::Redirect(new_module_url) => {
           // If redirects, update module_name and filename for next looped call.
           dir
@@ -411,27 +496,39 @@ impl SourceFileFetcher {
               &module_url,
               None,
               Some(new_module_url.to_string()),
+              Validators::default(),
             )
             .unwrap();
 
           // Explicit drop to keep reference alive until future completes.
           drop(download_job);
 
-          // Recurse
-          Either::Left(dir.fetch_remote_source_async(
-            &new_module_url,
-            use_disk_cache,
-            cached_only,
-            redirect_limit - 1,
-          ))
+          // Recurse, the final source must also match integrity pinned for
+          // the URL that redirected to it.
+          Either::Left(
+            dir
+              .fetch_remote_source_async(
+                &new_module_url,
+                use_disk_cache,
+                cached_only,
+                redirect_limit - 1,
+              )
+              .and_then(move |source_file| {
+                let result = dir
+                  .check_integrity(&module_url, &source_file)
+                  .map(|()| source_file);
+                futures::future::ready(result)
+              }),
+          )
         }
-        FetchOnceResult::Code(source, maybe_content_type) => {
+        FetchOnceResult::Code(source, maybe_content_type, validators) => {
           // We land on the code.
           dir
             .save_source_code_headers(
               &module_url,
               maybe_content_type.clone(),
               None,
+              validators,
             )
             .unwrap();
 
@@ -457,7 +554,10 @@ impl SourceFileFetcher {
           // Explicit drop to keep reference alive until future completes.
           drop(download_job);
 
-          Either::Right(futures::future::ok(source_file))
+          let result = dir
+            .check_integrity(&module_url, &source_file)
+            .map(|()| source_file);
+          Either::Right(futures::future::ready(result))
         }
       }
     });
@@ -465,6 +565,24 @@ impl SourceFileFetcher {
     f.boxed()
   }
 
+  /// Returns the redirects recorded in the disk cache for a remote module,
+  /// as `(from, to)` pairs in the order they are followed.
+  pub fn get_redirect_chain(self: &Self, url: &Url) -> Vec<(Url, Url)> {
+    let mut chain = vec![];
+    let mut current = url.clone();
+    // Same limit as used for downloads in `get_source_file_async`.
+    while chain.len() < 10 {
+      let headers = self.get_source_code_headers(&current);
+      let next = match headers.redirect_to.and_then(|r| Url::parse(&r).ok()) {
+        Some(next) => next,
+        None => break,
+      };
+      chain.push((current, next.clone()));
+      current = next;
+    }
+    chain
+  }
+
   /// Get header metadata associated with a remote file.
   ///
   /// NOTE: chances are that the source file was downloaded due to redirects.
@@ -484,6 +602,23 @@ impl SourceFileFetcher {
     SourceCodeHeaders::default()
   }
 
+  /// Get the validators to revalidate the cached copy of a remote file with.
+  /// Empty if there is no cached copy to fall back to.
+  fn get_cached_validators(self: &Self, url: &Url) -> Validators {
+    let headers = self.get_source_code_headers(url);
+    let filepath = self
+      .deps_cache
+      .location
+      .join(self.deps_cache.get_cache_filename(url));
+    if headers.redirect_to.is_some() || !filepath.is_file() {
+      return Validators::default();
+    }
+    Validators {
+      etag: headers.etag,
+      last_modified: headers.last_modified,
+    }
+  }
+
   /// Save contents of downloaded remote file in on-disk cache for subsequent access.
   fn save_source_code(
     self: &Self,
@@ -510,6 +645,7 @@ impl SourceFileFetcher {
     url: &Url,
     mime_type: Option<String>,
     redirect_to: Option<String>,
+    validators: Validators,
   ) -> std::io::Result<()> {
     let cache_key = self
       .deps_cache
@@ -522,6 +658,8 @@ impl SourceFileFetcher {
     let headers = SourceCodeHeaders {
       mime_type,
       redirect_to,
+      etag: validators.etag,
+      last_modified: validators.last_modified,
     };
 
     let cache_filename = self.deps_cache.get_cache_filename(url);
@@ -535,7 +673,7 @@ impl SourceFileFetcher {
   }
 }
 
-fn map_file_extension(path: &Path) -> msg::MediaType {
+pub fn map_file_extension(path: &Path) -> msg::MediaType {
   match path.extension() {
     None => msg::MediaType::Unknown,
     Some(os_str) => match os_str.to_str() {
@@ -643,10 +781,17 @@ pub struct SourceCodeHeaders {
   /// Where should we actually look for source code.
   /// This should be an absolute path!
   pub redirect_to: Option<String>,
+  /// `ETag` of the response, used to revalidate the cached source code.
+  pub etag: Option<String>,
+  /// `Last-Modified` of the response, used to revalidate the cached source
+  /// code.
+  pub last_modified: Option<String>,
 }
 
 static MIME_TYPE: &str = "mime_type";
 static REDIRECT_TO: &str = "redirect_to";
+static ETAG: &str = "etag";
+static LAST_MODIFIED: &str = "last_modified";
 
 impl SourceCodeHeaders {
   pub fn from_json_string(headers_string: String) -> Self {
@@ -657,10 +802,15 @@ impl SourceCodeHeaders {
     if let Ok(headers_json) = maybe_headers_json {
       let mime_type = headers_json[MIME_TYPE].as_str().map(String::from);
       let redirect_to = headers_json[REDIRECT_TO].as_str().map(String::from);
+      let etag = headers_json[ETAG].as_str().map(String::from);
+      let last_modified =
+        headers_json[LAST_MODIFIED].as_str().map(String::from);
 
       return SourceCodeHeaders {
         mime_type,
         redirect_to,
+        etag,
+        last_modified,
       };
     }
 
@@ -697,6 +847,14 @@ impl SourceCodeHeaders {
       value_map.insert(REDIRECT_TO.to_string(), json!(redirect_to));
     }
 
+    if let Some(etag) = &self.etag {
+      value_map.insert(ETAG.to_string(), json!(etag));
+    }
+
+    if let Some(last_modified) = &self.last_modified {
+      value_map.insert(LAST_MODIFIED.to_string(), json!(last_modified));
+    }
+
     if value_map.is_empty() {
       return Ok(None);
     }
@@ -721,6 +879,9 @@ mod tests {
       vec![],
       false,
       false,
+      IntegrityMap::new(),
+      AuthTokens::default(),
+      http_util::get_client(None).unwrap(),
     )
     .expect("setup fail")
   }
@@ -831,6 +992,7 @@ mod tests {
       &url,
       Some("text/typescript".to_owned()),
       Some("http://deno.land/a.js".to_owned()),
+      Validators::default(),
     );
     let headers2 = fetcher.get_source_code_headers(&url);
     assert_eq!(headers2.mime_type.clone().unwrap(), "text/typescript");
@@ -883,8 +1045,11 @@ mod tests {
           "export { printHello } from \"./print_hello.ts\";\n".as_bytes()
         );
         assert_eq!(&(r.media_type), &msg::MediaType::TypeScript);
-        // Should not create .headers.json file due to matching ext
-        assert!(fs::read_to_string(&headers_file_name_1).is_err());
+        // Should not save the mime type due to matching ext, but the
+        // validators to revalidate the file with.
+        let headers = fs::read_to_string(&headers_file_name_1).unwrap();
+        assert!(!headers.contains("mime_type"));
+        assert!(headers.contains("last_modified"));
 
         // Modify .headers.json, write using fs write and read using save_source_code_headers
         let _ = fs::write(
@@ -916,6 +1081,7 @@ mod tests {
           &module_url_1,
           Some("application/json".to_owned()),
           None,
+          Validators::default(),
         );
         fetcher_2.get_source_file_async(&module_url_1, true, false, false)
       })
@@ -944,9 +1110,11 @@ mod tests {
         let expected4 =
           "export { printHello } from \"./print_hello.ts\";\n".as_bytes();
         assert_eq!(r4.source_code, expected4);
-        // Now the old .headers.json file should have gone! Resolved back to TypeScript
+        // Now the old .headers.json file should have been replaced! Resolved back to TypeScript
         assert_eq!(&(r4.media_type), &msg::MediaType::TypeScript);
-        assert!(fs::read_to_string(&headers_file_name_3).is_err());
+        assert!(!fs::read_to_string(&headers_file_name_3)
+          .unwrap()
+          .contains("mime_type"));
         futures::future::ok(())
       });
 
@@ -992,6 +1160,7 @@ mod tests {
           &module_url,
           Some("text/typescript".to_owned()),
           None,
+          Validators::default(),
         );
         fetcher.get_source_file_async(&module_url, true, false, false)
       })
@@ -1362,13 +1531,16 @@ mod tests {
         let r = result.unwrap();
         assert_eq!(r.source_code, b"export const loaded = true;\n");
         assert_eq!(&(r.media_type), &msg::MediaType::TypeScript);
-        // matching ext, no .headers.json file created
-        assert!(fs::read_to_string(&headers_file_name).is_err());
+        // matching ext, no mime type saved in .headers.json
+        assert!(!fs::read_to_string(&headers_file_name)
+          .unwrap()
+          .contains("mime_type"));
         // Modify .headers.json, make sure read from local
         let _ = fetcher.save_source_code_headers(
           &module_url,
           Some("text/javascript".to_owned()),
           None,
+          Validators::default(),
         );
         let result2 = fetcher.fetch_cached_remote_source(&module_url);
         assert!(result2.is_ok());
@@ -1383,6 +1555,94 @@ mod tests {
     drop(http_server_guard);
   }
 
+  #[test]
+  fn test_fetch_source_revalidate() {
+    let http_server_guard = crate::test_util::http_server();
+    let (_temp_dir, fetcher) = test_setup();
+    let fetcher_1 = fetcher.clone();
+    let module_url =
+      Url::parse("http://127.0.0.1:4545/etag_script.ts").unwrap();
+    let module_url_1 = module_url.clone();
+    let source_file_name = fetcher
+      .deps_cache
+      .location
+      .join(fetcher.deps_cache.get_cache_filename(&module_url));
+
+    let fut = fetcher
+      .fetch_remote_source_async(&module_url, false, false, 10)
+      .then(move |result| {
+        assert_eq!(result.unwrap().source_code, b"console.log(\"etag\");\n");
+        let headers = fetcher.get_source_code_headers(&module_url);
+        assert_eq!(headers.etag, Some("33a64df551425fcc55e".to_string()));
+
+        // A 304 response must serve the cached copy.
+        fs::write(&source_file_name, "console.log(\"cached\");\n").unwrap();
+        fetcher_1.fetch_remote_source_async(&module_url_1, false, false, 10)
+      })
+      .then(move |result| {
+        assert_eq!(result.unwrap().source_code, b"console.log(\"cached\");\n");
+        futures::future::ok(())
+      });
+
+    tokio_util::run(fut);
+    drop(http_server_guard);
+  }
+
+  #[test]
+  fn test_fetch_source_integrity() {
+    let http_server_guard = crate::test_util::http_server();
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let good_url =
+      Url::parse("http://127.0.0.1:4545/tests/subdir/mt_video_mp2t.t3.ts")
+        .unwrap();
+    let bad_url =
+      Url::parse("http://127.0.0.1:4545/tests/subdir/mod2.ts").unwrap();
+    let mut integrity = IntegrityMap::new();
+    integrity.insert(
+      good_url.to_string(),
+      "sha256-Oj4ALi+S3I8EW9SnxmtHkUU60EF7A43SstnQ8nfETxg=".to_string(),
+    );
+    integrity.insert(
+      bad_url.to_string(),
+      "sha256-Oj4ALi+S3I8EW9SnxmtHkUU60EF7A43SstnQ8nfETxg=".to_string(),
+    );
+    let fetcher = SourceFileFetcher::new(
+      DiskCache::new(&temp_dir.path().join("deps")),
+      Progress::new(),
+      true,
+      vec![],
+      false,
+      false,
+      integrity,
+      AuthTokens::default(),
+      http_util::get_client(None).unwrap(),
+    )
+    .expect("setup fail");
+    let fetcher_1 = fetcher.clone();
+    let fetcher_2 = fetcher.clone();
+
+    let fut = fetcher
+      .fetch_remote_source_async(&good_url, false, false, 10)
+      .then(move |result| {
+        assert!(result.is_ok());
+        fetcher_1.fetch_remote_source_async(&bad_url, false, false, 10)
+      })
+      .then(move |result| {
+        let err = result.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::IntegrityCheckFailed);
+        // Cached source is verified as well.
+        fetcher_2.fetch_remote_source_async(&bad_url, true, false, 10)
+      })
+      .then(move |result| {
+        let err = result.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::IntegrityCheckFailed);
+        futures::future::ok(())
+      });
+
+    tokio_util::run(fut);
+    drop(http_server_guard);
+  }
+
   #[test]
   fn test_fetch_source_1() {
     let http_server_guard = crate::test_util::http_server();
@@ -1404,14 +1664,17 @@ mod tests {
         let r = result.unwrap();
         assert_eq!(r.source_code, "export const loaded = true;\n".as_bytes());
         assert_eq!(&(r.media_type), &msg::MediaType::TypeScript);
-        // matching ext, no .headers.json file created
-        assert!(fs::read_to_string(&headers_file_name).is_err());
+        // matching ext, no mime type saved in .headers.json
+        assert!(!fs::read_to_string(&headers_file_name)
+          .unwrap()
+          .contains("mime_type"));
 
         // Modify .headers.json, make sure read from local
         let _ = fetcher.save_source_code_headers(
           &module_url,
           Some("text/javascript".to_owned()),
           None,
+          Validators::default(),
         );
         let result2 = fetcher.fetch_cached_remote_source(&module_url);
         assert!(result2.is_ok());
@@ -1,4 +1,6 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use crate::fs as deno_fs;
+use crate::permissions::is_glob_char;
 use clap::App;
 use clap::AppSettings;
 use clap::Arg;
@@ -6,6 +8,7 @@ use clap::ArgMatches;
 use clap::SubCommand;
 use log::Level;
 use std::collections::HashSet;
+use std::path::is_separator;
 
 /// Creates vector of strings, Vec<String>
 macro_rules! svec {
@@ -38,6 +41,7 @@ const XEVAL_URL: &str = std_url!("xeval/mod.ts");
 #[derive(Clone, Debug, PartialEq)]
 pub enum DenoSubcommand {
   Bundle,
+  Cache,
   Completions,
   Eval,
   Fetch,
@@ -47,6 +51,7 @@ pub enum DenoSubcommand {
   Repl,
   Run,
   Types,
+  Vendor,
   Xeval,
 }
 
@@ -56,6 +61,15 @@ impl Default for DenoSubcommand {
   }
 }
 
+/// Action taken by `deno cache`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CacheSubcommand {
+  List,
+  Verify,
+  Prune,
+  Clear,
+}
+
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct DenoFlags {
   /// Vector of CLI arguments - these are user script arguments, all Deno
@@ -68,15 +82,22 @@ pub struct DenoFlags {
   pub reload: bool,
   pub config_path: Option<String>,
   pub import_map_path: Option<String>,
+  /// JSON file with permissions, merged with the permission flags.
+  pub permissions_path: Option<String>,
   pub allow_read: bool,
   pub read_whitelist: Vec<String>,
+  pub read_blacklist: Vec<String>,
   pub cache_blacklist: Vec<String>,
   pub allow_write: bool,
   pub write_whitelist: Vec<String>,
+  pub write_blacklist: Vec<String>,
   pub allow_net: bool,
   pub net_whitelist: Vec<String>,
+  pub net_blacklist: Vec<String>,
   pub allow_env: bool,
+  pub env_whitelist: Vec<String>,
   pub allow_run: bool,
+  pub run_whitelist: Vec<String>,
   pub allow_plugin: bool,
   pub allow_hrtime: bool,
   pub no_prompts: bool,
@@ -88,16 +109,26 @@ pub struct DenoFlags {
   pub current_thread: bool,
 
   pub bundle_output: Option<String>,
+  pub cache_subcommand: Option<CacheSubcommand>,
+  pub vendor_output: Option<String>,
 
   pub lock: Option<String>,
   pub lock_write: bool,
+  pub ca_file: Option<String>,
+  /// File that every permission check is recorded to.
+  pub permission_log: Option<String>,
+  /// Restart the program when one of its local modules changes.
+  pub watch: bool,
 }
 
 static ENV_VARIABLES_HELP: &str = "ENVIRONMENT VARIABLES:
-    DENO_DIR       Set deno's base directory
-    NO_COLOR       Set to disable color
-    HTTP_PROXY     Proxy address for HTTP requests (module downloads, fetch)
-    HTTPS_PROXY    Same but for HTTPS";
+    DENO_AUTH_TOKENS  Credentials sent with module downloads, a ';' separated
+                      list of token@host or username:password@host
+    DENO_CERT         Load certificate authority from PEM encoded file
+    DENO_DIR          Set deno's base directory
+    NO_COLOR          Set to disable color
+    HTTP_PROXY        Proxy address for HTTP requests (module downloads, fetch)
+    HTTPS_PROXY       Same but for HTTPS";
 
 static DENO_HELP: &str = "A secure JavaScript and TypeScript runtime
 
@@ -169,12 +200,16 @@ pub fn flags_from_vec_safe(args: Vec<String>) -> clap::Result<DenoFlags> {
     xeval_parse(&mut flags, m);
   } else if let Some(m) = matches.subcommand_matches("bundle") {
     bundle_parse(&mut flags, m);
+  } else if let Some(m) = matches.subcommand_matches("cache") {
+    cache_parse(&mut flags, m);
   } else if let Some(m) = matches.subcommand_matches("install") {
     install_parse(&mut flags, m);
   } else if let Some(m) = matches.subcommand_matches("completions") {
     completions_parse(&mut flags, m);
   } else if let Some(m) = matches.subcommand_matches("test") {
     test_parse(&mut flags, m);
+  } else if let Some(m) = matches.subcommand_matches("vendor") {
+    vendor_parse(&mut flags, m);
   } else {
     unimplemented!();
   }
@@ -205,6 +240,7 @@ fn clap_root<'a, 'b>() -> App<'a, 'b> {
         .global(true),
     )
     .subcommand(bundle_subcommand())
+    .subcommand(cache_subcommand())
     .subcommand(completions_subcommand())
     .subcommand(eval_subcommand())
     .subcommand(fetch_subcommand())
@@ -215,6 +251,7 @@ fn clap_root<'a, 'b>() -> App<'a, 'b> {
     .subcommand(run_subcommand())
     .subcommand(test_subcommand())
     .subcommand(types_subcommand())
+    .subcommand(vendor_subcommand())
     .subcommand(xeval_subcommand())
     .long_about(DENO_HELP)
     .after_help(ENV_VARIABLES_HELP)
@@ -311,6 +348,7 @@ fn install_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
 
 fn bundle_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Bundle;
+  ca_file_arg_parse(flags, matches);
   let source_file: &str = matches.value_of("source_file").unwrap();
   flags.argv.push(source_file.into());
   if let Some(out_file) = matches.value_of("out_file") {
@@ -319,6 +357,31 @@ fn bundle_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   }
 }
 
+fn cache_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
+  flags.subcommand = DenoSubcommand::Cache;
+  let cache_subcommand = match matches.subcommand() {
+    ("list", Some(_)) => CacheSubcommand::List,
+    ("verify", Some(m)) => {
+      config_arg_parse(flags, m);
+      CacheSubcommand::Verify
+    }
+    ("prune", Some(m)) => {
+      importmap_arg_parse(flags, m);
+      config_arg_parse(flags, m);
+      ca_file_arg_parse(flags, m);
+      // Resolving the module graph must not download anything, a module
+      // missing from the cache aborts the prune instead.
+      flags.cached_only = true;
+      let file: &str = m.value_of("file").unwrap();
+      flags.argv.push(file.into());
+      CacheSubcommand::Prune
+    }
+    ("clear", Some(_)) => CacheSubcommand::Clear,
+    _ => unreachable!(),
+  };
+  flags.cache_subcommand = Some(cache_subcommand);
+}
+
 fn completions_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Completions;
   let shell: &str = matches.value_of("shell").unwrap();
@@ -364,6 +427,7 @@ fn xeval_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
 
 fn repl_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   v8_flags_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
   flags.subcommand = DenoSubcommand::Repl;
   flags.allow_net = true;
   flags.allow_env = true;
@@ -376,6 +440,7 @@ fn repl_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
 
 fn eval_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Eval;
+  ca_file_arg_parse(flags, matches);
   flags.allow_net = true;
   flags.allow_env = true;
   flags.allow_run = true;
@@ -389,6 +454,7 @@ fn eval_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
 
 fn info_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Info;
+  ca_file_arg_parse(flags, matches);
   if let Some(file) = matches.value_of("file") {
     flags.argv.push(file.into());
   }
@@ -401,11 +467,24 @@ fn fetch_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   importmap_arg_parse(flags, matches);
   config_arg_parse(flags, matches);
   no_remote_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
   if let Some(file) = matches.value_of("file") {
     flags.argv.push(file.into());
   }
 }
 
+fn vendor_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
+  flags.subcommand = DenoSubcommand::Vendor;
+  reload_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
+  lock_args_parse(flags, matches);
+  config_arg_parse(flags, matches);
+  let source_file: &str = matches.value_of("source_file").unwrap();
+  flags.argv.push(source_file.into());
+  let out_dir: &str = matches.value_of("out_dir").unwrap();
+  flags.vendor_output = Some(out_dir.to_string());
+}
+
 fn lock_args_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   if matches.is_present("lock") {
     let lockfile = matches.value_of("lock").unwrap();
@@ -424,6 +503,11 @@ fn run_test_args_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   config_arg_parse(flags, matches);
   v8_flags_arg_parse(flags, matches);
   no_remote_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
+  flags.permission_log =
+    matches.value_of("permission-log").map(ToOwned::to_owned);
+  flags.permissions_path =
+    matches.value_of("permissions").map(ToOwned::to_owned);
 
   if matches.is_present("allow-read") {
     if matches.value_of("allow-read").is_some() {
@@ -458,11 +542,43 @@ fn run_test_args_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
       flags.allow_net = true;
     }
   }
+  if let Some(read_bl) = matches.values_of("deny-read") {
+    let raw_read_blacklist =
+      read_bl.map(std::string::ToString::to_string).collect();
+    flags.read_blacklist = resolve_paths(raw_read_blacklist);
+    debug!("read blacklist: {:#?}", &flags.read_blacklist);
+  }
+  if let Some(write_bl) = matches.values_of("deny-write") {
+    let raw_write_blacklist =
+      write_bl.map(std::string::ToString::to_string).collect();
+    flags.write_blacklist = resolve_paths(raw_write_blacklist);
+    debug!("write blacklist: {:#?}", &flags.write_blacklist);
+  }
+  if let Some(net_bl) = matches.values_of("deny-net") {
+    let raw_net_blacklist =
+      net_bl.map(std::string::ToString::to_string).collect();
+    flags.net_blacklist = resolve_hosts(raw_net_blacklist);
+    debug!("net blacklist: {:#?}", &flags.net_blacklist);
+  }
   if matches.is_present("allow-env") {
-    flags.allow_env = true;
+    if matches.value_of("allow-env").is_some() {
+      let env_wl = matches.values_of("allow-env").unwrap();
+      flags.env_whitelist =
+        env_wl.map(std::string::ToString::to_string).collect();
+      debug!("env whitelist: {:#?}", &flags.env_whitelist);
+    } else {
+      flags.allow_env = true;
+    }
   }
   if matches.is_present("allow-run") {
-    flags.allow_run = true;
+    if matches.value_of("allow-run").is_some() {
+      let run_wl = matches.values_of("allow-run").unwrap();
+      flags.run_whitelist =
+        run_wl.map(std::string::ToString::to_string).collect();
+      debug!("run whitelist: {:#?}", &flags.run_whitelist);
+    } else {
+      flags.allow_run = true;
+    }
   }
   if matches.is_present("allow-plugin") {
     flags.allow_plugin = true;
@@ -510,6 +626,9 @@ fn run_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Run;
   script_arg_parse(flags, matches);
   run_test_args_parse(flags, matches);
+  if matches.is_present("watch") {
+    flags.watch = true;
+  }
 }
 
 fn test_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
@@ -718,6 +837,7 @@ fn repl_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name("repl")
     .about("Read Eval Print Loop")
     .arg(v8_flags_arg())
+    .arg(ca_file_arg())
 }
 
 fn install_subcommand<'a, 'b>() -> App<'a, 'b> {
@@ -749,6 +869,10 @@ $HOME/.deno/bin and it must be added to the path manually.
 
   deno install colors https://deno.land/std/examples/colors.ts
 
+The executable refers to a permissions policy file by its absolute path
+
+  deno install file_server https://deno.land/std/http/file_server.ts --permissions=permissions.json
+
 To change installation directory use -d/--dir flag
 
   deno install -d /usr/local/bin file_server https://deno.land/std/http/file_server.ts --allow-net --allow-read")
@@ -756,6 +880,7 @@ To change installation directory use -d/--dir flag
 
 fn bundle_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name("bundle")
+    .arg(ca_file_arg())
     .arg(
       Arg::with_name("source_file")
         .takes_value(true)
@@ -836,6 +961,7 @@ Demonstrates breaking the input up by space delimiter instead of by lines:
 
 fn eval_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name("eval")
+    .arg(ca_file_arg())
     .about("Eval script")
     .long_about(
       "Evaluate JavaScript from command-line
@@ -849,6 +975,7 @@ This command has implicit access to all permissions (--allow-all)
 
 fn info_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name("info")
+    .arg(ca_file_arg())
     .about("Show info about cache or info related to source file")
     .long_about(
       "Information about source file and cache
@@ -872,9 +999,59 @@ TypeScript compiler cache: directory containing TS compiler output",
     .arg(Arg::with_name("file").takes_value(true).required(false))
 }
 
+fn cache_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name("cache")
+    .setting(AppSettings::SubcommandRequiredElseHelp)
+    .about("Manage the cache of remote modules and compiler output")
+    .long_about(
+      "Manage the cache of remote modules and compiler output.
+
+List cached remote modules with their size and fetch time
+
+  deno cache list
+
+Check cached compiler output against the source it was compiled from
+
+  deno cache verify
+
+Remove cached files not reachable from the given module
+
+  deno cache prune https://deno.land/std/http/file_server.ts
+
+Remove all cached files
+
+  deno cache clear",
+    )
+    .subcommand(
+      SubCommand::with_name("list").about("List cached remote modules"),
+    )
+    .subcommand(
+      SubCommand::with_name("verify")
+        .arg(config_arg())
+        .about("Verify cached compiler output against its source"),
+    )
+    .subcommand(
+      SubCommand::with_name("prune")
+        .arg(importmap_arg())
+        .arg(ca_file_arg())
+        .arg(config_arg())
+        .arg(Arg::with_name("file").takes_value(true).required(true))
+        .about("Remove cached files not reachable from a module")
+        .long_about(
+          "Remove cached files not reachable from a module.
+
+Only static imports are followed, modules that are dynamically imported
+are removed. Nothing is removed if a dependency of the module is not
+cached.",
+        ),
+    )
+    .subcommand(SubCommand::with_name("clear").about("Remove all cached files"))
+}
+
 fn fetch_subcommand<'a, 'b>() -> App<'a, 'b> {
   SubCommand::with_name("fetch")
     .arg(reload_arg())
+    .arg(ca_file_arg())
     .arg(lock_arg())
     .arg(lock_write_arg())
     .arg(importmap_arg())
@@ -899,6 +1076,38 @@ Once cached, static imports no longer send network requests
     )
 }
 
+fn vendor_subcommand<'a, 'b>() -> App<'a, 'b> {
+  SubCommand::with_name("vendor")
+    .arg(reload_arg())
+    .arg(ca_file_arg())
+    .arg(lock_arg())
+    .arg(lock_write_arg())
+    .arg(config_arg())
+    .arg(
+      Arg::with_name("source_file")
+        .takes_value(true)
+        .required(true),
+    )
+    .arg(Arg::with_name("out_dir").takes_value(true).required(true))
+    .about("Copy remote dependencies into a local directory")
+    .long_about(
+      "Copy remote dependencies into a local directory.
+
+Downloads all statically imported remote modules and writes them to out_dir,
+using the same layout as the remote modules cache. An import map that
+redirects the original URLs to the copied files is written to
+out_dir/import_map.json.
+
+  deno vendor https://deno.land/std/http/file_server.ts vendor
+
+The copied modules are then used instead of the remote ones with
+
+  deno run --importmap=vendor/import_map.json https://deno.land/std/http/file_server.ts
+
+Modules that are only imported dynamically are not copied.",
+    )
+}
+
 fn run_test_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
   app
     .arg(importmap_arg())
@@ -908,6 +1117,7 @@ fn run_test_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     .arg(lock_write_arg())
     .arg(no_remote_arg())
     .arg(v8_flags_arg())
+    .arg(ca_file_arg())
     .arg(
       Arg::with_name("allow-read")
         .long("allow-read")
@@ -935,14 +1145,49 @@ fn run_test_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
         .require_equals(true)
         .help("Allow network access"),
     )
+    .arg(
+      Arg::with_name("deny-read")
+        .long("deny-read")
+        .min_values(1)
+        .takes_value(true)
+        .use_delimiter(true)
+        .require_equals(true)
+        .help("Deny file system read access, even where it is allowed"),
+    )
+    .arg(
+      Arg::with_name("deny-write")
+        .long("deny-write")
+        .min_values(1)
+        .takes_value(true)
+        .use_delimiter(true)
+        .require_equals(true)
+        .help("Deny file system write access, even where it is allowed"),
+    )
+    .arg(
+      Arg::with_name("deny-net")
+        .long("deny-net")
+        .min_values(1)
+        .takes_value(true)
+        .use_delimiter(true)
+        .require_equals(true)
+        .help("Deny network access, even where it is allowed"),
+    )
     .arg(
       Arg::with_name("allow-env")
         .long("allow-env")
+        .min_values(0)
+        .takes_value(true)
+        .use_delimiter(true)
+        .require_equals(true)
         .help("Allow environment access"),
     )
     .arg(
       Arg::with_name("allow-run")
         .long("allow-run")
+        .min_values(0)
+        .takes_value(true)
+        .use_delimiter(true)
+        .require_equals(true)
         .help("Allow running subprocesses"),
     )
     .arg(
@@ -961,6 +1206,21 @@ fn run_test_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
         .long("allow-all")
         .help("Allow all permissions"),
     )
+    .arg(
+      Arg::with_name("permissions")
+        .long("permissions")
+        .value_name("FILE")
+        .help("Grant the permissions listed in a JSON policy file")
+        .takes_value(true)
+        .require_equals(true),
+    )
+    .arg(
+      Arg::with_name("permission-log")
+        .long("permission-log")
+        .value_name("FILE")
+        .help("Append a JSON line to FILE for every permission check")
+        .takes_value(true),
+    )
     .arg(
       Arg::with_name("cached-only")
         .long("cached-only")
@@ -987,6 +1247,11 @@ fn run_test_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
 fn run_subcommand<'a, 'b>() -> App<'a, 'b> {
   run_test_args(SubCommand::with_name("run"))
     .setting(AppSettings::TrailingVarArg)
+    .arg(
+      Arg::with_name("watch")
+        .long("watch")
+        .help("Restart the program when one of its local modules changes"),
+    )
     .arg(script_arg())
     .about("Run a program given a filename or url to the source code")
     .long_about(
@@ -1007,7 +1272,19 @@ With only permission to read from disk and listen to network
 
 With only permission to read whitelist files from disk
 
-  deno run --allow-read=/etc https://deno.land/std/http/file_server.ts",
+  deno run --allow-read=/etc https://deno.land/std/http/file_server.ts
+
+With the permissions listed in a policy file
+
+  deno run --permissions=permissions.json script.ts
+
+With read access to the home directory, except for ssh keys
+
+  deno run --allow-read=$HOME --deny-read=$HOME/.ssh script.ts
+
+Restarting whenever one of the local modules it imports changes
+
+  deno run --watch server.ts",
     )
 }
 
@@ -1077,7 +1354,7 @@ fn lock_arg<'a, 'b>() -> Arg<'a, 'b> {
 fn lock_write_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("lock-write")
     .long("lock-write")
-    .help("Write lock file. Use with --lock.")
+    .help("Write lock file. Use with --lock. Migrates old lock files.")
 }
 
 fn config_arg<'a, 'b>() -> Arg<'a, 'b> {
@@ -1163,6 +1440,18 @@ fn v8_flags_arg_parse(flags: &mut DenoFlags, matches: &ArgMatches) {
   }
 }
 
+fn ca_file_arg<'a, 'b>() -> Arg<'a, 'b> {
+  Arg::with_name("cert")
+    .long("cert")
+    .value_name("FILE")
+    .help("Load certificate authority from PEM encoded file")
+    .takes_value(true)
+}
+
+fn ca_file_arg_parse(flags: &mut DenoFlags, matches: &clap::ArgMatches) {
+  flags.ca_file = matches.value_of("cert").map(ToOwned::to_owned);
+}
+
 fn no_remote_arg<'a, 'b>() -> Arg<'a, 'b> {
   Arg::with_name("no-remote")
     .long("no-remote")
@@ -1197,10 +1486,37 @@ pub fn resolve_urls(urls: Vec<String>) -> Vec<String> {
   out
 }
 
+/// Resolves relative paths against the current directory, as the paths that
+/// permissions are checked against are absolute. Of a glob, the components
+/// before the first pattern are resolved and the rest is kept as given.
+fn resolve_paths(paths: Vec<String>) -> Vec<String> {
+  paths
+    .into_iter()
+    .map(|path| {
+      let (prefix, pattern) = match path.find(is_glob_char) {
+        None => (path.as_str(), ""),
+        Some(glob_start) => match path[..glob_start].rfind(is_separator) {
+          Some(i) => path.split_at(i + 1),
+          None => (".", path.as_str()),
+        },
+      };
+      let resolved = match deno_fs::resolve_from_cwd(prefix) {
+        Ok((resolved, _)) => resolved,
+        Err(_) => return path,
+      };
+      if pattern.is_empty() {
+        resolved.to_string_lossy().into_owned()
+      } else {
+        resolved.join(pattern).to_string_lossy().into_owned()
+      }
+    })
+    .collect()
+}
+
 /// Expands "bare port" paths (eg. ":8080") into full paths with hosts. It
 /// expands to such paths into 3 paths with following hosts: `0.0.0.0:port`,
 /// `127.0.0.1:port` and `localhost:port`.
-fn resolve_hosts(paths: Vec<String>) -> Vec<String> {
+pub fn resolve_hosts(paths: Vec<String>) -> Vec<String> {
   let mut out: Vec<String> = vec![];
   for host_and_port in paths.iter() {
     let parts = host_and_port.split(':').collect::<Vec<&str>>();
@@ -1246,6 +1562,7 @@ fn arg_hacks(mut args: Vec<String>) -> Vec<String> {
   }
   let subcommands = sset![
     "bundle",
+    "cache",
     "completions",
     "eval",
     "fetch",
@@ -1255,6 +1572,7 @@ fn arg_hacks(mut args: Vec<String>) -> Vec<String> {
     "repl",
     "run",
     "types",
+    "vendor",
     "install",
     "help",
     "version",
@@ -1530,6 +1848,64 @@ mod tests {
     );
   }
 
+  #[test]
+  fn cache() {
+    let r = flags_from_vec_safe(svec!["deno", "cache", "list"]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Cache,
+        cache_subcommand: Some(CacheSubcommand::List),
+        argv: svec!["deno"],
+        ..DenoFlags::default()
+      }
+    );
+
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "cache",
+      "verify",
+      "--config",
+      "tsconfig.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Cache,
+        cache_subcommand: Some(CacheSubcommand::Verify),
+        config_path: Some("tsconfig.json".to_owned()),
+        argv: svec!["deno"],
+        ..DenoFlags::default()
+      }
+    );
+
+    let r = flags_from_vec_safe(svec!["deno", "cache", "prune", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Cache,
+        cache_subcommand: Some(CacheSubcommand::Prune),
+        cached_only: true,
+        argv: svec!["deno", "script.ts"],
+        ..DenoFlags::default()
+      }
+    );
+
+    let r = flags_from_vec_safe(svec!["deno", "cache", "clear"]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Cache,
+        cache_subcommand: Some(CacheSubcommand::Clear),
+        argv: svec!["deno"],
+        ..DenoFlags::default()
+      }
+    );
+
+    let r = flags_from_vec_safe(svec!["deno", "cache"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn info() {
     let r = flags_from_vec_safe(svec!["deno", "info", "script.ts"]);
@@ -1721,6 +2097,107 @@ mod tests {
     );
   }
 
+  #[test]
+  fn allow_env_and_run_whitelists() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--allow-env=HOME,PATH",
+      "--allow-run=git,curl",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        allow_env: false,
+        env_whitelist: svec!["HOME", "PATH"],
+        allow_run: false,
+        run_whitelist: svec!["git", "curl"],
+        ..DenoFlags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn allow_env_and_run_without_whitelists() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--allow-env",
+      "--allow-run",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        allow_env: true,
+        allow_run: true,
+        ..DenoFlags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn deny_blacklists() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--allow-read",
+      "--deny-read=/home/user/.ssh,/srv/**/*.key",
+      "--deny-write=/etc",
+      "--deny-net=*.example.com:443,:8080",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        allow_read: true,
+        read_blacklist: svec!["/home/user/.ssh", "/srv/**/*.key"],
+        write_blacklist: svec!["/etc"],
+        net_blacklist: svec![
+          "*.example.com:443",
+          "0.0.0.0:8080",
+          "127.0.0.1:8080",
+          "localhost:8080"
+        ],
+        ..DenoFlags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn deny_read_relative() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--allow-read",
+      "--deny-read=.ssh,secrets/../keys,secrets/*.key,*.pem",
+      "script.ts"
+    ]);
+    let cwd = std::env::current_dir().unwrap();
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        allow_read: true,
+        read_blacklist: svec![
+          cwd.join(".ssh").to_str().unwrap(),
+          cwd.join("keys").to_str().unwrap(),
+          cwd.join("secrets/*.key").to_str().unwrap(),
+          cwd.join("*.pem").to_str().unwrap()
+        ],
+        ..DenoFlags::default()
+      }
+    );
+  }
+
   #[test]
   fn fmt_stdout() {
     let r = flags_from_vec_safe(svec![
@@ -1814,6 +2291,23 @@ mod tests {
     );
   }
 
+  #[test]
+  fn vendor() {
+    let r = flags_from_vec_safe(svec!["deno", "vendor", "source.ts", "vendor"]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Vendor,
+        argv: svec!["deno", "source.ts"],
+        vendor_output: Some("vendor".to_string()),
+        ..DenoFlags::default()
+      }
+    );
+
+    let r = flags_from_vec_safe(svec!["deno", "vendor", "source.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_importmap() {
     let r = flags_from_vec_safe(svec![
@@ -2288,4 +2782,107 @@ mod tests {
       }
     );
   }
+
+  #[test]
+  fn run_with_cert() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--cert",
+      "example.crt",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        ca_file: Some("example.crt".to_owned()),
+        ..DenoFlags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_permissions() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--permissions=permissions.json",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        permissions_path: Some("permissions.json".to_owned()),
+        ..DenoFlags::default()
+      }
+    );
+
+    // The installer relies on the file being given with "=".
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--permissions",
+      "permissions.json",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_permission_log() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "run",
+      "--permission-log",
+      "permissions.log",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        permission_log: Some("permissions.log".to_owned()),
+        ..DenoFlags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_watch() {
+    let r = flags_from_vec_safe(svec!["deno", "run", "--watch", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Run,
+        argv: svec!["deno", "script.ts"],
+        watch: true,
+        ..DenoFlags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn fetch_with_cert() {
+    let r = flags_from_vec_safe(svec![
+      "deno",
+      "fetch",
+      "--cert",
+      "example.crt",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      DenoFlags {
+        subcommand: DenoSubcommand::Fetch,
+        argv: svec!["deno", "script.ts"],
+        ca_file: Some("example.crt".to_owned()),
+        ..DenoFlags::default()
+      }
+    );
+  }
 }
@@ -1,4 +1,5 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use crate::auth_tokens::AuthTokens;
 use crate::compilers::CompiledModule;
 use crate::compilers::JsCompiler;
 use crate::compilers::JsonCompiler;
@@ -6,16 +7,24 @@ use crate::compilers::TsCompiler;
 use crate::compilers::WasmCompiler;
 use crate::deno_dir;
 use crate::deno_error::permission_denied;
+use crate::file_fetcher::SourceFile;
 use crate::file_fetcher::SourceFileFetcher;
 use crate::flags;
+use crate::http_util;
+use crate::import_map::ImportMap;
+use crate::import_map::IntegrityMap;
 use crate::lockfile::Lockfile;
+use crate::lockfile::LockfileEntry;
 use crate::metrics::Metrics;
 use crate::msg;
 use crate::permissions::DenoPermissions;
+use crate::permissions::PermissionLog;
+use crate::permissions::PermissionPolicy;
 use crate::progress::Progress;
 use deno::ErrBox;
 use deno::ModuleSpecifier;
 use futures::future::TryFutureExt;
+use reqwest::r#async::Client;
 use std;
 use std::env;
 use std::future::Future;
@@ -38,6 +47,8 @@ pub struct GlobalState {
   pub main_module: Option<ModuleSpecifier>,
   /// Permissions parsed from `flags`.
   pub permissions: DenoPermissions,
+  /// Audit log of permission checks, from `--permission-log`.
+  pub permission_log: Option<PermissionLog>,
   pub dir: deno_dir::DenoDir,
   pub metrics: Metrics,
   pub progress: Progress,
@@ -47,6 +58,11 @@ pub struct GlobalState {
   pub ts_compiler: TsCompiler,
   pub wasm_compiler: WasmCompiler,
   pub lockfile: Option<Mutex<Lockfile>>,
+  /// PEM file with additional trusted root certificates, from `--cert` or
+  /// `$DENO_CERT`.
+  pub ca_file: Option<String>,
+  /// Client for module downloads and `fetch()`.
+  pub http_client: Client,
 }
 
 impl Clone for ThreadSafeGlobalState {
@@ -70,6 +86,17 @@ impl ThreadSafeGlobalState {
     let custom_root = env::var("DENO_DIR").map(String::into).ok();
     let dir = deno_dir::DenoDir::new(custom_root)?;
 
+    // Integrity metadata is shared by all workers, so it is read here rather
+    // than from the import map loaded by each worker's `State`.
+    let integrity = match flags.import_map_path.as_ref() {
+      None => IntegrityMap::new(),
+      Some(file_path) => ImportMap::load(file_path)?.integrity().clone(),
+    };
+
+    let ca_file = flags.ca_file.clone().or_else(|| env::var("DENO_CERT").ok());
+    let http_client =
+      http_util::get_client(ca_file.as_ref().map(String::as_str))?;
+
     let file_fetcher = SourceFileFetcher::new(
       dir.deps_cache.clone(),
       progress.clone(),
@@ -77,6 +104,9 @@ impl ThreadSafeGlobalState {
       flags.cache_blacklist.clone(),
       flags.no_remote,
       flags.cached_only,
+      integrity,
+      AuthTokens::new(env::var("DENO_AUTH_TOKENS").ok()),
+      http_client.clone(),
     )?;
 
     let ts_compiler = TsCompiler::new(
@@ -100,10 +130,21 @@ impl ThreadSafeGlobalState {
       None
     };
 
+    let permission_log = match flags.permission_log.as_ref() {
+      Some(filename) => Some(PermissionLog::open(filename)?),
+      None => None,
+    };
+
+    let mut permissions = DenoPermissions::from_flags(&flags);
+    if let Some(filename) = flags.permissions_path.as_ref() {
+      permissions.merge_policy(&PermissionPolicy::load(filename)?);
+    }
+
     let state = GlobalState {
       main_module,
       dir,
-      permissions: DenoPermissions::from_flags(&flags),
+      permissions,
+      permission_log,
       flags,
       metrics: Metrics::default(),
       progress,
@@ -113,6 +154,8 @@ impl ThreadSafeGlobalState {
       json_compiler: JsonCompiler {},
       wasm_compiler: WasmCompiler::default(),
       lockfile,
+      ca_file,
+      http_client,
     };
 
     Ok(ThreadSafeGlobalState(Arc::new(state)))
@@ -125,10 +168,18 @@ impl ThreadSafeGlobalState {
   ) -> impl Future<Output = Result<CompiledModule, ErrBox>> {
     let state1 = self.clone();
     let state2 = self.clone();
+    let module_specifier = module_specifier.clone();
 
     self
       .file_fetcher
       .fetch_source_file_async(&module_specifier, maybe_referrer)
+      .and_then(move |out| {
+        // Verify the source against the lockfile before compiling it.
+        match state2.check_lockfile(&module_specifier, &out) {
+          Ok(()) => futures::future::ok(out),
+          Err(e) => futures::future::err(e),
+        }
+      })
       .and_then(move |out| match out.media_type {
         msg::MediaType::Unknown => state1.js_compiler.compile_async(&out),
         msg::MediaType::Json => state1.json_compiler.compile_async(&out),
@@ -148,57 +199,108 @@ impl ThreadSafeGlobalState {
           }
         }
       })
-      .and_then(move |compiled_module| {
-        if let Some(ref lockfile) = state2.lockfile {
-          let mut g = lockfile.lock().unwrap();
-          if state2.flags.lock_write {
-            g.insert(&compiled_module);
-          } else {
-            let check = match g.check(&compiled_module) {
-              Err(e) => return futures::future::err(ErrBox::from(e)),
-              Ok(v) => v,
-            };
-            if !check {
-              eprintln!(
-                "Subresource integrety check failed --lock={}\n{}",
-                g.filename, compiled_module.name
-              );
-              std::process::exit(10);
-            }
-          }
+  }
+
+  /// With `--lock-write` records the module, its source checksum and any
+  /// redirects leading to it. With `--lock` exits the process if they do not
+  /// match the lockfile, and fails if the lockfile cannot be read.
+  fn check_lockfile(
+    self: &Self,
+    module_specifier: &ModuleSpecifier,
+    source_file: &SourceFile,
+  ) -> Result<(), ErrBox> {
+    let lockfile = match self.lockfile {
+      Some(ref lockfile) => lockfile,
+      None => return Ok(()),
+    };
+    let mut entries: Vec<(url::Url, LockfileEntry)> = self
+      .file_fetcher
+      .get_redirect_chain(module_specifier.as_url())
+      .into_iter()
+      .map(|(from, to)| (from, LockfileEntry::Redirect(to.to_string())))
+      .collect();
+    entries.push((
+      source_file.url.clone(),
+      LockfileEntry::from_source_file(source_file),
+    ));
+
+    let mut g = lockfile.lock().unwrap();
+    for (url, entry) in entries {
+      if self.flags.lock_write {
+        g.insert(&url, entry);
+        continue;
+      }
+      match g.check(&url, &entry) {
+        Err(e) => return Err(ErrBox::from(e)),
+        Ok(false) => {
+          eprintln!(
+            "Subresource integrety check failed --lock={}\n{}",
+            g.filename, url
+          );
+          std::process::exit(10);
         }
-        futures::future::ok(compiled_module)
-      })
+        Ok(true) => {}
+      }
+    }
+    Ok(())
+  }
+
+  /// Records the result of a permission check in the `--permission-log`
+  /// file, if there is one.
+  pub fn log_permission_check<T>(
+    &self,
+    kind: &str,
+    target: Option<&str>,
+    result: &Result<T, ErrBox>,
+    op: Option<&str>,
+  ) {
+    if let Some(log) = &self.permission_log {
+      let result = if result.is_ok() { "granted" } else { "denied" };
+      log.write(kind, target, result, op);
+    }
   }
 
   #[inline]
   pub fn check_read(&self, filename: &str) -> Result<(), ErrBox> {
-    self.permissions.check_read(filename)
+    let result = self.permissions.check_read(filename);
+    self.log_permission_check("read", Some(filename), &result, None);
+    result
   }
 
   #[inline]
   pub fn check_write(&self, filename: &str) -> Result<(), ErrBox> {
-    self.permissions.check_write(filename)
+    let result = self.permissions.check_write(filename);
+    self.log_permission_check("write", Some(filename), &result, None);
+    result
   }
 
   #[inline]
   pub fn check_env(&self) -> Result<(), ErrBox> {
-    self.permissions.check_env()
+    let result = self.permissions.check_env();
+    self.log_permission_check("env", None, &result, None);
+    result
   }
 
   #[inline]
   pub fn check_net(&self, hostname: &str, port: u16) -> Result<(), ErrBox> {
-    self.permissions.check_net(hostname, port)
+    let result = self.permissions.check_net(hostname, port);
+    let target = format!("{}:{}", hostname, port);
+    self.log_permission_check("net", Some(&target), &result, None);
+    result
   }
 
   #[inline]
   pub fn check_net_url(&self, url: &url::Url) -> Result<(), ErrBox> {
-    self.permissions.check_net_url(url)
+    let result = self.permissions.check_net_url(url);
+    self.log_permission_check("net", Some(url.as_str()), &result, None);
+    result
   }
 
   #[inline]
   pub fn check_run(&self) -> Result<(), ErrBox> {
-    self.permissions.check_run()
+    let result = self.permissions.check_run();
+    self.log_permission_check("run", None, &result, None);
+    result
   }
 
   pub fn check_dyn_import(
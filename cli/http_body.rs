@@ -2,25 +2,28 @@
 
 use futures::io::AsyncRead;
 use futures::stream::StreamExt;
-use reqwest::r#async::Chunk;
 use reqwest::r#async::Decoder;
 use std::cmp::min;
+use std::error::Error;
 use std::io;
 use std::io::Read;
+use std::ops::Deref;
 use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
+use tokio::prelude::Stream as Stream01;
 
 /// Wraps `reqwest::Decoder` so that it can be exposed as an `AsyncRead` and integrated
-/// into resources more easily.
-pub struct HttpBody {
-  decoder: futures::compat::Compat01As03<Decoder>,
-  chunk: Option<Chunk>,
+/// into resources more easily. Also used for `hyper::Body` of requests received by
+/// the HTTP server.
+pub struct HttpBody<S: Stream01 = Decoder> {
+  decoder: futures::compat::Compat01As03<S>,
+  chunk: Option<S::Item>,
   pos: usize,
 }
 
-impl HttpBody {
-  pub fn from(body: Decoder) -> Self {
+impl<S: Stream01> HttpBody<S> {
+  pub fn from(body: S) -> Self {
     Self {
       decoder: futures::compat::Compat01As03::new(body),
       chunk: None,
@@ -29,13 +32,18 @@ impl HttpBody {
   }
 }
 
-impl Read for HttpBody {
+impl<S: Stream01> Read for HttpBody<S> {
   fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
     unimplemented!();
   }
 }
 
-impl AsyncRead for HttpBody {
+impl<S> AsyncRead for HttpBody<S>
+where
+  S: Stream01,
+  S::Item: Deref<Target = [u8]> + Unpin,
+  S::Error: Into<Box<dyn Error + Send + Sync>>,
+{
   fn poll_read(
     self: Pin<&mut Self>,
     cx: &mut Context,
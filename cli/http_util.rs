@@ -1,6 +1,8 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use crate::auth_tokens::AuthTokens;
 use crate::deno_error;
 use crate::deno_error::DenoError;
+use crate::ops::tls::load_certs;
 use crate::version;
 use deno::ErrBox;
 use futures::future;
@@ -8,29 +10,42 @@ use futures::future::FutureExt;
 use futures::future::TryFutureExt;
 use reqwest;
 use reqwest::header::HeaderMap;
+use reqwest::header::HeaderName;
+use reqwest::header::AUTHORIZATION;
 use reqwest::header::CONTENT_TYPE;
+use reqwest::header::ETAG;
+use reqwest::header::IF_MODIFIED_SINCE;
+use reqwest::header::IF_NONE_MATCH;
+use reqwest::header::LAST_MODIFIED;
 use reqwest::header::LOCATION;
 use reqwest::header::USER_AGENT;
 use reqwest::r#async::Client;
 use reqwest::RedirectPolicy;
+use reqwest::StatusCode;
 use std::future::Future;
 use std::pin::Pin;
 use url::Url;
 
 /// Create new instance of async reqwest::Client. This client supports
-/// proxies and doesn't follow redirects.
-pub fn get_client() -> Client {
+/// proxies and doesn't follow redirects. Certificates in the PEM file
+/// `ca_file` are trusted in addition to the built-in roots.
+pub fn get_client(ca_file: Option<&str>) -> Result<Client, ErrBox> {
   let mut headers = HeaderMap::new();
   headers.insert(
     USER_AGENT,
     format!("Deno/{}", version::DENO).parse().unwrap(),
   );
-  Client::builder()
+  let mut builder = Client::builder()
     .redirect(RedirectPolicy::none())
     .default_headers(headers)
-    .use_sys_proxy()
-    .build()
-    .unwrap()
+    .use_sys_proxy();
+  if let Some(ca_file) = ca_file {
+    for cert in load_certs(ca_file)? {
+      builder =
+        builder.add_root_certificate(reqwest::Certificate::from_der(&cert.0)?);
+    }
+  }
+  Ok(builder.build()?)
 }
 
 /// Construct the next uri based on base uri and location header fragment
@@ -60,32 +75,84 @@ fn resolve_url_from_location(base_url: &Url, location: &str) -> Url {
   }
 }
 
+fn header_string(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+  headers
+    .get(name)
+    .and_then(|value| value.to_str().ok())
+    .map(String::from)
+}
+
+/// Cache validators of a response, sent with a later request for the same
+/// URL to make it conditional.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Validators {
+  /// Value of the `ETag` header.
+  pub etag: Option<String>,
+  /// Value of the `Last-Modified` header.
+  pub last_modified: Option<String>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum FetchOnceResult {
-  // (code, maybe_content_type)
-  Code(String, Option<String>),
+  // (code, maybe_content_type, validators)
+  Code(String, Option<String>, Validators),
   Redirect(Url),
+  /// The server confirmed the copy described by the given validators is
+  /// still fresh.
+  NotModified,
 }
 
 /// Asynchronously fetchs the given HTTP URL one pass only.
 /// If no redirect is present and no error occurs,
-/// yields Code(code, maybe_content_type).
+/// yields Code(code, maybe_content_type, validators).
 /// If redirect occurs, does not follow and
 /// yields Redirect(url).
+/// If `cached` has validators the request is conditional and yields
+/// NotModified if the server responds with 304.
+/// The token in `auth_tokens` for the host of `url`, if any, is sent as the
+/// `Authorization` header.
 pub fn fetch_string_once(
+  client: &Client,
   url: &Url,
+  cached: &Validators,
+  auth_tokens: &AuthTokens,
 ) -> impl Future<Output = Result<FetchOnceResult, ErrBox>> {
-  type FetchAttempt = (Option<String>, Option<String>, Option<FetchOnceResult>);
+  type FetchAttempt = (
+    Option<(String, Validators)>,
+    Option<String>,
+    Option<FetchOnceResult>,
+  );
 
   let url = url.clone();
-  let client = get_client();
 
-  futures::compat::Compat01As03::new(client.get(url.clone()).send())
+  let mut request = client.get(url.clone());
+  if let Some(auth_token) = auth_tokens.get(&url) {
+    request = request.header(AUTHORIZATION, auth_token.header_value());
+  }
+  if let Some(etag) = &cached.etag {
+    request = request.header(IF_NONE_MATCH, etag.as_str());
+  }
+  if let Some(last_modified) = &cached.last_modified {
+    request = request.header(IF_MODIFIED_SINCE, last_modified.as_str());
+  }
+
+  futures::compat::Compat01As03::new(request.send())
     .map_err(ErrBox::from)
     .and_then(
       move |mut response| -> Pin<
         Box<dyn Future<Output = Result<FetchAttempt, ErrBox>> + Send>,
       > {
+        // Must be checked before redirects, 304 is in the same class.
+        if response.status() == StatusCode::NOT_MODIFIED {
+          debug!("Not modified {:?}", &url);
+          return futures::future::try_join3(
+            future::ok(None),
+            future::ok(None),
+            future::ok(Some(FetchOnceResult::NotModified)),
+          )
+          .boxed();
+        }
+
         if response.status().is_redirection() {
           let location_string = response
             .headers()
@@ -123,8 +190,13 @@ pub fn fetch_string_once(
           .get(CONTENT_TYPE)
           .map(|content_type| content_type.to_str().unwrap().to_owned());
 
+        let validators = Validators {
+          etag: header_string(response.headers(), ETAG),
+          last_modified: header_string(response.headers(), LAST_MODIFIED),
+        };
+
         let body = futures::compat::Compat01As03::new(response.text())
-          .map_ok(Some)
+          .map_ok(move |code| Some((code, validators)))
           .map_err(ErrBox::from);
 
         futures::future::try_join3(
@@ -135,15 +207,13 @@ pub fn fetch_string_once(
         .boxed()
       },
     )
-    .and_then(move |(maybe_code, maybe_content_type, maybe_redirect)| {
-      if let Some(redirect) = maybe_redirect {
-        future::ok(redirect)
+    .and_then(move |(maybe_code, maybe_content_type, maybe_result)| {
+      if let Some(result) = maybe_result {
+        future::ok(result)
       } else {
         // maybe_code should always contain code here!
-        future::ok(FetchOnceResult::Code(
-          maybe_code.unwrap(),
-          maybe_content_type,
-        ))
+        let (code, validators) = maybe_code.unwrap();
+        future::ok(FetchOnceResult::Code(code, maybe_content_type, validators))
       }
     })
 }
@@ -160,14 +230,86 @@ mod tests {
     let url =
       Url::parse("http://127.0.0.1:4545/cli/tests/fixture.json").unwrap();
 
-    let fut = fetch_string_once(&url).then(|result| match result {
-      Ok(FetchOnceResult::Code(code, maybe_content_type)) => {
-        assert!(!code.is_empty());
-        assert_eq!(maybe_content_type, Some("application/json".to_string()));
-        futures::future::ok(())
-      }
-      _ => panic!(),
-    });
+    let cached = Validators::default();
+    let auth_tokens = AuthTokens::default();
+    let client = get_client(None).unwrap();
+    let fut =
+      fetch_string_once(&client, &url, &cached, &auth_tokens).then(|result| {
+        match result {
+          Ok(FetchOnceResult::Code(code, maybe_content_type, validators)) => {
+            assert!(!code.is_empty());
+            assert_eq!(
+              maybe_content_type,
+              Some("application/json".to_string())
+            );
+            assert!(validators.last_modified.is_some());
+            futures::future::ok(())
+          }
+          _ => panic!(),
+        }
+      });
+
+    tokio_util::run(fut);
+    drop(http_server_guard);
+  }
+
+  #[test]
+  fn test_fetch_string_once_not_modified() {
+    let http_server_guard = crate::test_util::http_server();
+    // Relies on external http server. See tools/http_server.py
+    let url = Url::parse("http://127.0.0.1:4545/etag_script.ts").unwrap();
+    let url_ = url.clone();
+
+    let cached = Validators::default();
+    let auth_tokens = AuthTokens::default();
+    let client = get_client(None).unwrap();
+    let fut = fetch_string_once(&client, &url, &cached, &auth_tokens)
+      .then(move |result| match result {
+        Ok(FetchOnceResult::Code(code, _, validators)) => {
+          assert_eq!(code, "console.log(\"etag\");\n");
+          assert_eq!(validators.etag, Some("33a64df551425fcc55e".to_string()));
+          fetch_string_once(&client, &url_, &validators, &auth_tokens)
+        }
+        _ => panic!(),
+      })
+      .then(|result| match result {
+        Ok(FetchOnceResult::NotModified) => futures::future::ok(()),
+        _ => panic!(),
+      });
+
+    tokio_util::run(fut);
+    drop(http_server_guard);
+  }
+
+  #[test]
+  fn test_fetch_string_once_auth_token() {
+    let http_server_guard = crate::test_util::http_server();
+    // Relies on external http server. See tools/http_server.py
+    let url = Url::parse("http://127.0.0.1:4545/auth_echo.ts").unwrap();
+    let url_ = url.clone();
+
+    let cached = Validators::default();
+    let auth_tokens =
+      AuthTokens::new(Some("abc123@127.0.0.1:4545".to_string()));
+    let other_auth_tokens =
+      AuthTokens::new(Some("abc123@deno.land".to_string()));
+    let client = get_client(None).unwrap();
+    let fut = fetch_string_once(&client, &url, &cached, &auth_tokens)
+      .then(move |result| match result {
+        Ok(FetchOnceResult::Code(code, _, _)) => {
+          assert_eq!(code, "Bearer abc123");
+          // Tokens for other hosts are not sent.
+          fetch_string_once(&client, &url_, &cached, &other_auth_tokens)
+        }
+        _ => panic!(),
+      })
+      .then(|result| match result {
+        Ok(FetchOnceResult::Code(code, _, _)) => {
+          assert_eq!(code, "");
+          futures::future::ok(())
+        }
+        _ => panic!(),
+      });
 
     tokio_util::run(fut);
     drop(http_server_guard);
@@ -182,13 +324,18 @@ mod tests {
     // Dns resolver substitutes `127.0.0.1` with `localhost`
     let target_url =
       Url::parse("http://localhost:4545/cli/tests/fixture.json").unwrap();
-    let fut = fetch_string_once(&url).then(move |result| match result {
-      Ok(FetchOnceResult::Redirect(url)) => {
-        assert_eq!(url, target_url);
-        futures::future::ok(())
-      }
-      _ => panic!(),
-    });
+    let cached = Validators::default();
+    let auth_tokens = AuthTokens::default();
+    let client = get_client(None).unwrap();
+    let fut = fetch_string_once(&client, &url, &cached, &auth_tokens).then(
+      move |result| match result {
+        Ok(FetchOnceResult::Redirect(url)) => {
+          assert_eq!(url, target_url);
+          futures::future::ok(())
+        }
+        _ => panic!(),
+      },
+    );
 
     tokio_util::run(fut);
     drop(http_server_guard);
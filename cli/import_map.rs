@@ -35,14 +35,21 @@ impl Error for ImportMapError {}
 //  can't resolve URL with other schemes (eg. data:, about:, blob:)
 const SUPPORTED_FETCH_SCHEMES: [&str; 3] = ["http", "https", "file"];
 
+// Hash algorithms accepted in the "integrity" section, as used by
+// Subresource Integrity metadata.
+const SUPPORTED_INTEGRITY_ALGORITHMS: [&str; 3] =
+  ["sha256", "sha384", "sha512"];
+
 type SpecifierMap = IndexMap<String, Vec<ModuleSpecifier>>;
 type ScopesMap = IndexMap<String, SpecifierMap>;
+pub type IntegrityMap = IndexMap<String, String>;
 
 #[derive(Debug)]
 pub struct ImportMap {
   base_url: String,
   imports: SpecifierMap,
   scopes: ScopesMap,
+  integrity: IntegrityMap,
 }
 
 impl ImportMap {
@@ -116,10 +123,25 @@ impl ImportMap {
       None => IndexMap::new(),
     };
 
+    let normalized_integrity = match &v.get("integrity") {
+      Some(integrity_map) => {
+        if !integrity_map.is_object() {
+          return Err(ImportMapError::new(
+            "Import map's 'integrity' must be an object",
+          ));
+        }
+
+        let integrity_map = integrity_map.as_object().unwrap();
+        ImportMap::parse_integrity_map(integrity_map, base_url)
+      }
+      None => IndexMap::new(),
+    };
+
     let import_map = ImportMap {
       base_url: base_url.to_string(),
       imports: normalized_imports,
       scopes: normalized_scopes,
+      integrity: normalized_integrity,
     };
 
     Ok(import_map)
@@ -326,6 +348,60 @@ impl ImportMap {
     Ok(normalized_map)
   }
 
+  /// Convert provided JSON map to valid IntegrityMap.
+  ///
+  /// Keys must be URL-like and are resolved against the base URL, values are
+  /// Subresource Integrity strings such as "sha384-<base64 digest>". Invalid
+  /// entries are skipped.
+  fn parse_integrity_map(
+    json_map: &Map<String, Value>,
+    base_url: &str,
+  ) -> IntegrityMap {
+    let mut normalized_map: IntegrityMap = IntegrityMap::new();
+
+    for (url_key, value) in json_map.iter() {
+      let url = match ImportMap::try_url_like_specifier(url_key, base_url) {
+        Some(url) => url.to_string(),
+        None => {
+          eprintln!(
+            "Invalid integrity key {:?}. Integrity keys must be URLs.",
+            url_key
+          );
+          continue;
+        }
+      };
+
+      let integrity = match value {
+        Value::String(integrity) => integrity.trim().to_string(),
+        _ => continue,
+      };
+
+      let is_supported =
+        integrity
+          .split_whitespace()
+          .all(|metadata| match metadata.find('-') {
+            Some(i) => SUPPORTED_INTEGRITY_ALGORITHMS.contains(&&metadata[..i]),
+            None => false,
+          });
+      if integrity.is_empty() || !is_supported {
+        eprintln!(
+          "Invalid integrity {:?} for {:?}. Supported algorithms: {:?}",
+          integrity, url, SUPPORTED_INTEGRITY_ALGORITHMS
+        );
+        continue;
+      }
+
+      normalized_map.insert(url, integrity);
+    }
+
+    normalized_map
+  }
+
+  /// Returns the integrity metadata pinned for resolved URLs.
+  pub fn integrity(&self) -> &IntegrityMap {
+    &self.integrity
+  }
+
   pub fn resolve_scopes_match(
     scopes: &ScopesMap,
     normalized_specifier: &str,
@@ -532,6 +608,41 @@ mod tests {
     assert!(result.is_ok());
   }
 
+  #[test]
+  fn from_json_integrity_not_object() {
+    let json_map = r#"{ "integrity": ["sha256-abc"] }"#;
+    let result = ImportMap::from_json("https://deno.land", json_map);
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn parse_integrity() {
+    let json_map = r#"{
+      "integrity": {
+        "./foo/bar.ts": "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=",
+        "https://deno.land/std/fs/mod.ts": " sha384-abc sha512-def ",
+        "https://deno.land/x/md5.ts": "md5-abc",
+        "https://deno.land/x/empty.ts": "",
+        "https://deno.land/x/number.ts": 1,
+        "bare": "sha256-abc"
+      }
+    }"#;
+    let import_map =
+      ImportMap::from_json("https://deno.land/app/import_map.json", json_map)
+        .unwrap();
+
+    let integrity = import_map.integrity();
+    assert_eq!(integrity.len(), 2);
+    assert_eq!(
+      integrity.get("https://deno.land/app/foo/bar.ts").unwrap(),
+      "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="
+    );
+    assert_eq!(
+      integrity.get("https://deno.land/std/fs/mod.ts").unwrap(),
+      "sha384-abc sha512-def"
+    );
+  }
+
   #[test]
   fn parse_specifier_keys_relative() {
     // Should absolutize strings prefixed with ./, ../, or / into the corresponding URLs..
@@ -1249,6 +1360,7 @@ mod tests {
       base_url: "https://example.com/app/main.ts".to_string(),
       imports: IndexMap::new(),
       scopes: IndexMap::new(),
+      integrity: IndexMap::new(),
     }
   }
 
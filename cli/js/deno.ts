@@ -1,7 +1,16 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 
 // Public deno module.
-export { env, exit, isTTY, execPath, homeDir, hostname } from "./os.ts";
+export {
+  env,
+  exit,
+  isTTY,
+  setRaw,
+  consoleSize,
+  execPath,
+  homeDir,
+  hostname
+} from "./os.ts";
 export { chdir, cwd } from "./dir.ts";
 export {
   File,
@@ -16,8 +25,19 @@ export {
   writeSync,
   seek,
   seekSync,
+  flock,
+  flockSync,
+  funlock,
+  funlockSync,
+  fsync,
+  fsyncSync,
+  fdatasync,
+  fdatasyncSync,
+  ftruncate,
+  ftruncateSync,
   close,
-  OpenMode
+  OpenMode,
+  OpenOptions
 } from "./files.ts";
 export {
   EOF,
@@ -61,7 +81,14 @@ export { readFileSync, readFile } from "./read_file.ts";
 export { readDirSync, readDir } from "./read_dir.ts";
 export { copyFileSync, copyFile } from "./copy_file.ts";
 export { readlinkSync, readlink } from "./read_link.ts";
-export { statSync, lstatSync, stat, lstat } from "./stat.ts";
+export {
+  statSync,
+  lstatSync,
+  stat,
+  lstat,
+  fstatSync,
+  fstat
+} from "./stat.ts";
 export { linkSync, link } from "./link.ts";
 export { symlinkSync, symlink } from "./symlink.ts";
 export { writeFileSync, writeFile, WriteFileOptions } from "./write_file.ts";
@@ -75,20 +102,37 @@ export {
   Permissions
 } from "./permissions.ts";
 export { truncateSync, truncate } from "./truncate.ts";
+export { watchFs, FsEvent, FsWatcher, WatchFsOptions } from "./fs_events.ts";
 export { FileInfo } from "./file_info.ts";
 export { openPlugin } from "./plugins.ts";
-export { connect, dial, listen, Listener, Conn } from "./net.ts";
+export {
+  connect,
+  dial,
+  listen,
+  Listener,
+  Conn,
+  DatagramConn
+} from "./net.ts";
 export { dialTLS, listenTLS } from "./tls.ts";
+export {
+  serveHttp,
+  HttpServer,
+  HttpRequest,
+  HttpRequestBody,
+  HttpResponse
+} from "./http.ts";
 export { metrics, Metrics } from "./metrics.ts";
 export { resources } from "./resources.ts";
 export {
   kill,
   run,
   RunOptions,
+  KillOptions,
   Process,
   ProcessStatus,
   Signal
 } from "./process.ts";
+export { signal, signals, SignalStream } from "./signals.ts";
 export { inspect, customInspect } from "./console.ts";
 export { build, OperatingSystem, Arch } from "./build.ts";
 export { version } from "./version.ts";
@@ -8,6 +8,8 @@ export let OP_READ: number;
 export let OP_WRITE: number;
 export let OP_EXIT: number;
 export let OP_IS_TTY: number;
+export let OP_SET_RAW: number;
+export let OP_CONSOLE_SIZE: number;
 export let OP_ENV: number;
 export let OP_EXEC_PATH: number;
 export let OP_UTIME: number;
@@ -22,7 +24,23 @@ export let OP_FETCH_SOURCE_FILES: number;
 export let OP_OPEN: number;
 export let OP_CLOSE: number;
 export let OP_SEEK: number;
+export let OP_FLOCK: number;
+export let OP_FUNLOCK: number;
+export let OP_FSYNC: number;
+export let OP_FDATASYNC: number;
+export let OP_FTRUNCATE: number;
+export let OP_FSTAT: number;
 export let OP_FETCH: number;
+export let OP_FETCH_CANCEL_HANDLE: number;
+export let OP_FETCH_CANCEL: number;
+export let OP_HTTP_SERVE: number;
+export let OP_HTTP_NEXT_REQUEST: number;
+export let OP_HTTP_RESPOND: number;
+export let OP_FS_EVENTS_OPEN: number;
+export let OP_FS_EVENTS_POLL: number;
+export let OP_SIGNAL_BIND: number;
+export let OP_SIGNAL_UNBIND: number;
+export let OP_SIGNAL_POLL: number;
 export let OP_METRICS: number;
 export let OP_REPL_START: number;
 export let OP_REPL_READLINE: number;
@@ -31,6 +49,8 @@ export let OP_ACCEPT_TLS: number;
 export let OP_DIAL: number;
 export let OP_SHUTDOWN: number;
 export let OP_LISTEN: number;
+export let OP_SEND_TO: number;
+export let OP_RECV_FROM: number;
 export let OP_LISTEN_TLS: number;
 export let OP_RESOURCES: number;
 export let OP_GET_RANDOM_VALUES: number;
@@ -49,6 +69,7 @@ export let OP_WORKER_GET_MESSAGE: number;
 export let OP_RUN: number;
 export let OP_RUN_STATUS: number;
 export let OP_KILL: number;
+export let OP_PTY_RESIZE: number;
 export let OP_CHDIR: number;
 export let OP_MKDIR: number;
 export let OP_CHMOD: number;
@@ -92,12 +113,23 @@ export function asyncMsgFromRust(opId: number, ui8: Uint8Array): void {
     case OP_UTIME:
     case OP_OPEN:
     case OP_SEEK:
+    case OP_FLOCK:
+    case OP_FUNLOCK:
+    case OP_FSYNC:
+    case OP_FDATASYNC:
+    case OP_FTRUNCATE:
+    case OP_FSTAT:
     case OP_FETCH:
+    case OP_HTTP_NEXT_REQUEST:
+    case OP_SIGNAL_POLL:
+    case OP_FS_EVENTS_POLL:
     case OP_REPL_START:
     case OP_REPL_READLINE:
     case OP_ACCEPT:
     case OP_ACCEPT_TLS:
     case OP_DIAL:
+    case OP_SEND_TO:
+    case OP_RECV_FROM:
     case OP_GLOBAL_TIMER:
     case OP_HOST_GET_WORKER_CLOSED:
     case OP_HOST_GET_MESSAGE:
@@ -539,8 +539,19 @@ type ResponseType =
   | "opaque"
   | "opaqueredirect";
 
+/** A reader backed by a resource, like `Deno.File` or a child process' stdout.
+ */
+export interface ResourceReader {
+  readonly rid: number;
+  read(p: Uint8Array): Promise<number | symbol>;
+}
+
 export interface RequestInit {
-  body?: BodyInit | null;
+  /** A body to send with the request. Besides the standard body types,
+   * `fetch()` accepts a reader with a resource id, like a `Deno.File`, which
+   * is streamed rather than buffered. `Request` does not accept one.
+   */
+  body?: BodyInit | ResourceReader | null;
   cache?: RequestCache;
   credentials?: RequestCredentials;
   headers?: HeadersInit;
@@ -551,7 +562,14 @@ export interface RequestInit {
   redirect?: RequestRedirect;
   referrer?: string;
   referrerPolicy?: ReferrerPolicy;
+  /** Aborting the signal cancels the request while it waits for the response
+   * headers. Reading the response body is not covered.
+   */
   signal?: AbortSignal | null;
+  /** Milliseconds `fetch()` waits for the response headers before it fails
+   * with `ErrorKind.TimedOut`. Reading the response body is not covered.
+   */
+  timeout?: number;
   window?: any;
 }
 
@@ -3,7 +3,8 @@ import {
   assert,
   createResolvable,
   notImplemented,
-  isTypedArray
+  isTypedArray,
+  isResourceReader
 } from "./util.ts";
 import * as domTypes from "./dom_types.ts";
 import { TextDecoder, TextEncoder } from "./text_encoding.ts";
@@ -15,7 +16,8 @@ import { Buffer } from "./buffer.ts";
 import { FormData } from "./form_data.ts";
 import { URLSearchParams } from "./url_search_params.ts";
 import * as dispatch from "./dispatch.ts";
-import { sendAsync } from "./dispatch_json.ts";
+import { sendAsync, sendSync } from "./dispatch_json.ts";
+import { DenoError, ErrorKind } from "./errors.ts";
 
 function getHeaderValueParams(value: string): Map<string, string> {
   const params = new Map();
@@ -344,7 +346,10 @@ async function sendFetchReq(
   url: string,
   method: string | null,
   headers: domTypes.Headers | null,
-  body: ArrayBufferView | undefined
+  body: ArrayBufferView | undefined,
+  bodyRid: number | undefined,
+  signal: domTypes.AbortSignal | undefined | null,
+  timeout: number | undefined
 ): Promise<FetchResponse> {
   let headerArray: Array<[string, string]> = [];
   if (headers) {
@@ -356,16 +361,45 @@ async function sendFetchReq(
     zeroCopy = new Uint8Array(body.buffer, body.byteOffset, body.byteLength);
   }
 
-  const args = {
-    method,
-    url,
-    headers: headerArray
-  };
+  if (signal == null) {
+    const args = { method, url, headers: headerArray, bodyRid, timeout };
+    const res = await sendAsync(dispatch.OP_FETCH, args, zeroCopy);
+    return res as FetchResponse;
+  }
 
-  return (await sendAsync(dispatch.OP_FETCH, args, zeroCopy)) as FetchResponse;
+  if (signal.aborted) {
+    throw new DenoError(ErrorKind.HttpCanceled, "Request has been canceled");
+  }
+  const cancelRid = sendSync(dispatch.OP_FETCH_CANCEL_HANDLE) as number;
+  const onAbort = (): void => {
+    sendSync(dispatch.OP_FETCH_CANCEL, { rid: cancelRid });
+  };
+  signal.addEventListener("abort", onAbort);
+  try {
+    const args = {
+      method,
+      url,
+      headers: headerArray,
+      bodyRid,
+      timeout,
+      cancelRid
+    };
+    const res = await sendAsync(dispatch.OP_FETCH, args, zeroCopy);
+    return res as FetchResponse;
+  } finally {
+    signal.removeEventListener("abort", onAbort);
+    close(cancelRid);
+  }
 }
 
-/** Fetch a resource from the network. */
+/** Fetch a resource from the network.
+ *
+ * Besides the standard body types, `init.body` may be a reader with a
+ * resource id, like a `Deno.File`, which is streamed rather than buffered.
+ * Aborting `init.signal` cancels the request, and `init.timeout` limits how
+ * many milliseconds to wait for the response headers. Neither covers reading
+ * the response body.
+ */
 export async function fetch(
   input: domTypes.Request | string,
   init?: domTypes.RequestInit
@@ -374,6 +408,7 @@ export async function fetch(
   let method: string | null = null;
   let headers: domTypes.Headers | null = null;
   let body: ArrayBufferView | undefined;
+  let bodyRid: number | undefined;
   let redirected = false;
   let remRedirectCount = 20; // TODO: use a better way to handle
 
@@ -409,6 +444,8 @@ export async function fetch(
         } else if (init.body instanceof DenoBlob) {
           body = init.body[blobBytesSymbol];
           contentType = init.body.type;
+        } else if (isResourceReader(init.body)) {
+          bodyRid = init.body.rid;
         } else {
           // TODO: FormData, ReadableStream
           notImplemented();
@@ -430,7 +467,18 @@ export async function fetch(
   }
 
   while (remRedirectCount) {
-    const fetchResponse = await sendFetchReq(url, method, headers, body);
+    const fetchResponse = await sendFetchReq(
+      url,
+      method,
+      headers,
+      body,
+      bodyRid,
+      init && init.signal,
+      init && init.timeout
+    );
+    // A streamed body is consumed by the first request, it cannot be sent
+    // again when following redirects.
+    bodyRid = undefined;
 
     const response = new Response(
       url,
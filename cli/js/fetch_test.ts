@@ -231,6 +231,76 @@ testPerm({ net: true }, async function fetchInitBlobBody(): Promise<void> {
   assert(response.headers.get("content-type").startsWith("text/javascript"));
 });
 
+testPerm(
+  { net: true, read: true },
+  async function fetchInitResourceBody(): Promise<void> {
+    const file = await Deno.open("cli/tests/fixture.json");
+    const response = await fetch("http://localhost:4545/echo_server", {
+      method: "POST",
+      body: file
+    });
+    file.close();
+    const text = await response.text();
+    const data = Deno.readFileSync("cli/tests/fixture.json");
+    assertEquals(text, new TextDecoder().decode(data));
+  }
+);
+
+testPerm(
+  { net: true, read: true },
+  async function fetchInitClosedResourceBody(): Promise<void> {
+    const file = await Deno.open("cli/tests/fixture.json");
+    file.close();
+    let err;
+    try {
+      await fetch("http://localhost:4545/echo_server", {
+        method: "POST",
+        body: file
+      });
+    } catch (err_) {
+      err = err_;
+    }
+    assert(!!err);
+    assertEquals(err.kind, Deno.ErrorKind.BadResource);
+  }
+);
+
+testPerm({ net: true }, async function fetchAbort(): Promise<void> {
+  const listener = Deno.listen({ port: 4505 });
+  const target = new EventTarget();
+  const signal = Object.assign(target, { aborted: false, onabort: null });
+  const accepted = listener.accept().then(conn => {
+    target.dispatchEvent(new Event("abort"));
+    return conn;
+  });
+  let err;
+  try {
+    await fetch("http://127.0.0.1:4505/", { signal });
+  } catch (err_) {
+    err = err_;
+  }
+  assert(!!err);
+  assertEquals(err.kind, Deno.ErrorKind.HttpCanceled);
+  (await accepted).close();
+  listener.close();
+});
+
+testPerm({ net: true }, async function fetchTimeout(): Promise<void> {
+  // The listener accepts the connection but never responds.
+  const listener = Deno.listen({ port: 4508 });
+  const accepted = listener.accept();
+  let err;
+  try {
+    await fetch("http://127.0.0.1:4508/", { timeout: 100 });
+  } catch (err_) {
+    err = err_;
+  }
+  assert(!!err);
+  assertEquals(err.kind, Deno.ErrorKind.TimedOut);
+  (await accepted).close();
+  listener.close();
+});
+
 testPerm({ net: true }, async function fetchUserAgent(): Promise<void> {
   const data = "Hello World";
   const response = await fetch("http://localhost:4545/echo_server", {
@@ -17,25 +17,41 @@ import {
   sendAsync as sendAsyncJson
 } from "./dispatch_json.ts";
 
+function openArgs(
+  filename: string,
+  mode: OpenMode | OpenOptions
+): { filename: string; mode?: OpenMode; options?: OpenOptions } {
+  if (typeof mode === "string") {
+    return { filename, mode };
+  }
+  return { filename, options: mode };
+}
+
 /** Open a file and return an instance of the `File` object
- *  synchronously.
+ *  synchronously. `mode` is either an `OpenMode` or `OpenOptions`.
  *
  *       const file = Deno.openSync("/foo/bar.txt");
+ *       const lock = Deno.openSync("lock", { write: true, createNew: true });
  */
-export function openSync(filename: string, mode: OpenMode = "r"): File {
-  const rid = sendSyncJson(dispatch.OP_OPEN, { filename, mode });
+export function openSync(
+  filename: string,
+  mode: OpenMode | OpenOptions = "r"
+): File {
+  const rid = sendSyncJson(dispatch.OP_OPEN, openArgs(filename, mode));
   return new File(rid);
 }
 
-/** Open a file and return an instance of the `File` object.
+/** Open a file and return an instance of the `File` object. `mode` is either
+ * an `OpenMode` or `OpenOptions`.
  *
  *       const file = await Deno.open("/foo/bar.txt");
+ *       const log = await Deno.open("log", { append: true, create: true });
  */
 export async function open(
   filename: string,
-  mode: OpenMode = "r"
+  mode: OpenMode | OpenOptions = "r"
 ): Promise<File> {
-  const rid = await sendAsyncJson(dispatch.OP_OPEN, { filename, mode });
+  const rid = await sendAsyncJson(dispatch.OP_OPEN, openArgs(filename, mode));
   return new File(rid);
 }
 
@@ -139,6 +155,96 @@ export async function seek(
   await sendAsyncJson(dispatch.OP_SEEK, { rid, offset, whence });
 }
 
+/** Takes an advisory lock on the file ID synchronously, blocking until other
+ * holders have released it. The lock is shared unless `exclusive` is true.
+ * Only supported on Unix.
+ *
+ *       const file = Deno.openSync("/foo/bar.txt", "r+");
+ *       Deno.flockSync(file.rid, true);
+ */
+export function flockSync(rid: number, exclusive = false): void {
+  sendSyncJson(dispatch.OP_FLOCK, { rid, exclusive });
+}
+
+/** Takes an advisory lock on the file ID. Resolves once other holders have
+ * released it. The lock is shared unless `exclusive` is true. Only supported
+ * on Unix.
+ *
+ *       const file = await Deno.open("/foo/bar.txt", "r+");
+ *       await Deno.flock(file.rid, true);
+ */
+export async function flock(rid: number, exclusive = false): Promise<void> {
+  await sendAsyncJson(dispatch.OP_FLOCK, { rid, exclusive });
+}
+
+/** Releases the lock taken with `flock()` synchronously. Closing the file
+ * releases it as well.
+ *
+ *       Deno.funlockSync(file.rid);
+ */
+export function funlockSync(rid: number): void {
+  sendSyncJson(dispatch.OP_FUNLOCK, { rid });
+}
+
+/** Releases the lock taken with `flock()`. Closing the file releases it as
+ * well.
+ *
+ *       await Deno.funlock(file.rid);
+ */
+export async function funlock(rid: number): Promise<void> {
+  await sendAsyncJson(dispatch.OP_FUNLOCK, { rid });
+}
+
+/** Flushes the data and metadata of the file ID to disk synchronously.
+ *
+ *       Deno.fsyncSync(file.rid);
+ */
+export function fsyncSync(rid: number): void {
+  sendSyncJson(dispatch.OP_FSYNC, { rid });
+}
+
+/** Flushes the data and metadata of the file ID to disk.
+ *
+ *       await Deno.fsync(file.rid);
+ */
+export async function fsync(rid: number): Promise<void> {
+  await sendAsyncJson(dispatch.OP_FSYNC, { rid });
+}
+
+/** Flushes the data of the file ID to disk synchronously, without metadata
+ * that is not needed to read it back, like the modification time.
+ *
+ *       Deno.fdatasyncSync(file.rid);
+ */
+export function fdatasyncSync(rid: number): void {
+  sendSyncJson(dispatch.OP_FDATASYNC, { rid });
+}
+
+/** Flushes the data of the file ID to disk, without metadata that is not
+ * needed to read it back, like the modification time.
+ *
+ *       await Deno.fdatasync(file.rid);
+ */
+export async function fdatasync(rid: number): Promise<void> {
+  await sendAsyncJson(dispatch.OP_FDATASYNC, { rid });
+}
+
+/** Truncates or extends the file ID synchronously to `len` bytes.
+ *
+ *       Deno.ftruncateSync(file.rid, 10);
+ */
+export function ftruncateSync(rid: number, len = 0): void {
+  sendSyncJson(dispatch.OP_FTRUNCATE, { rid, len });
+}
+
+/** Truncates or extends the file ID to `len` bytes.
+ *
+ *       await Deno.ftruncate(file.rid, 10);
+ */
+export async function ftruncate(rid: number, len = 0): Promise<void> {
+  await sendAsyncJson(dispatch.OP_FTRUNCATE, { rid, len });
+}
+
 /** Close the file ID. */
 export function close(rid: number): void {
   sendSyncJson(dispatch.OP_CLOSE, { rid });
@@ -218,6 +324,31 @@ export type OpenMode =
   /** Read-write. Behaves like `x` and allows to read from file. */
   | "x+";
 
+/** Options for `open()`, as an alternative to `OpenMode`. Opening for reading
+ * requires the `--allow-read` flag, any option that may create or change the
+ * file requires `--allow-write`.
+ */
+export interface OpenOptions {
+  /** Open for reading. */
+  read?: boolean;
+  /** Open for writing. */
+  write?: boolean;
+  /** Open for writing, every write goes to the end of the file. */
+  append?: boolean;
+  /** Truncate an existing file to 0 bytes. Requires `write`. */
+  truncate?: boolean;
+  /** Create the file if it does not exist. Requires `write` or `append`. */
+  create?: boolean;
+  /** Create the file, failing if it already exists. Requires `write` or
+   * `append`.
+   */
+  createNew?: boolean;
+  /** Permission bits of a created file, like `0o600`, before the umask is
+   * applied. Ignored on Windows.
+   */
+  mode?: number;
+}
+
 /** A factory function for creating instances of `File` associated with the
  * supplied file name.
  * @internal
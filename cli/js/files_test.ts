@@ -236,6 +236,42 @@ testPerm(
   }
 );
 
+testPerm(
+  { read: true, write: true },
+  async function openOptionsCreateNew(): Promise<void> {
+    const tempDir = Deno.makeTempDirSync();
+    const filename = tempDir + "/lock";
+    const options = { write: true, createNew: true, mode: 0o600 };
+    const file = await Deno.open(filename, options);
+    file.close();
+    if (Deno.build.os !== "win") {
+      assertEquals(Deno.statSync(filename).mode! & 0o777, 0o600);
+    }
+
+    let err;
+    try {
+      Deno.openSync(filename, options);
+    } catch (e) {
+      err = e;
+    }
+    assert(!!err);
+    assertEquals(err.kind, Deno.ErrorKind.AlreadyExists);
+    await Deno.remove(tempDir, { recursive: true });
+  }
+);
+
+testPerm({ read: true }, async function openOptionsPerm(): Promise<void> {
+  let err;
+  try {
+    await Deno.open("tests/hello.txt", { read: true, append: true });
+  } catch (e) {
+    err = e;
+  }
+  assert(!!err);
+  assertEquals(err.kind, Deno.ErrorKind.PermissionDenied);
+  assertEquals(err.name, "PermissionDenied");
+});
+
 testPerm({ read: true }, async function seekStart(): Promise<void> {
   const filename = "tests/hello.txt";
   const file = await Deno.open(filename);
@@ -327,3 +363,57 @@ testPerm({ read: true }, async function seekMode(): Promise<void> {
   await file.read(buf); // "H"
   assertEquals(new TextDecoder().decode(buf), "H");
 });
+
+testPerm({ read: true, write: true }, async function flockExclusive(): Promise<
+  void
+> {
+  if (Deno.build.os === "win") {
+    return;
+  }
+  const filename = Deno.makeTempDirSync() + "/lock.txt";
+  // Two opens of the same file hold locks independently.
+  const file1 = Deno.openSync(filename, "w+");
+  const file2 = Deno.openSync(filename, "r+");
+  Deno.flockSync(file1.rid, true);
+  let acquired = false;
+  const pending = Deno.flock(file2.rid, true).then((): void => {
+    acquired = true;
+  });
+  await new Promise((resolve): number => setTimeout(resolve, 100));
+  assert(!acquired);
+  Deno.funlockSync(file1.rid);
+  await pending;
+  assert(acquired);
+  await Deno.funlock(file2.rid);
+  file1.close();
+  file2.close();
+});
+
+testPerm({ read: true, write: true }, async function fileSyncStat(): Promise<
+  void
+> {
+  const filename = Deno.makeTempDirSync() + "/test.txt";
+  const file = await Deno.open(filename, "w+");
+  await file.write(new TextEncoder().encode("hello world"));
+  await Deno.fsync(file.rid);
+  Deno.fdatasyncSync(file.rid);
+  assertEquals((await Deno.fstat(file.rid)).len, 11);
+  await Deno.ftruncate(file.rid, 5);
+  assertEquals(Deno.fstatSync(file.rid).len, 5);
+  Deno.ftruncateSync(file.rid);
+  const fileInfo = Deno.fstatSync(file.rid);
+  assert(fileInfo.isFile());
+  assertEquals(fileInfo.len, 0);
+  file.close();
+});
+
+testPerm({ read: true }, function fstatBadResource(): void {
+  let err;
+  try {
+    Deno.fstatSync(Deno.stdin.rid);
+  } catch (e) {
+    err = e;
+  }
+  assert(!!err);
+  assertEquals(err.kind, Deno.ErrorKind.BadResource);
+});
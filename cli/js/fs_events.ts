@@ -0,0 +1,71 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+import * as dispatch from "./dispatch.ts";
+import { sendSync, sendAsync } from "./dispatch_json.ts";
+import { close } from "./files.ts";
+
+export interface FsEvent {
+  kind: "create" | "modify" | "remove" | "rename";
+  /** The source and, when known, the destination of a rename. */
+  paths: string[];
+}
+
+export interface FsWatcher extends AsyncIterator<FsEvent[]> {
+  readonly rid: number;
+  /** Stops watching. Pending iterations resolve as done. */
+  close(): void;
+  [Symbol.asyncIterator](): AsyncIterator<FsEvent[]>;
+}
+
+export interface WatchFsOptions {
+  /** Also watch all directories below the paths. Defaults to true. */
+  recursive?: boolean;
+}
+
+class FsWatcherImpl implements FsWatcher {
+  readonly rid: number;
+  private closed = false;
+
+  constructor(paths: string[], options: WatchFsOptions) {
+    const { recursive = true } = options;
+    this.rid = sendSync(dispatch.OP_FS_EVENTS_OPEN, { paths, recursive }).rid;
+  }
+
+  async next(): Promise<IteratorResult<FsEvent[]>> {
+    const res = await sendAsync(dispatch.OP_FS_EVENTS_POLL, { rid: this.rid });
+    if (res.done) {
+      return { value: undefined, done: true };
+    }
+    return { value: res.events, done: false };
+  }
+
+  async return(value?: FsEvent[]): Promise<IteratorResult<FsEvent[]>> {
+    this.close();
+    return { value, done: true };
+  }
+
+  close(): void {
+    if (!this.closed) {
+      this.closed = true;
+      close(this.rid);
+    }
+  }
+
+  [Symbol.asyncIterator](): AsyncIterator<FsEvent[]> {
+    return this;
+  }
+}
+
+/** Watches the paths for changes. Changes arriving close together are
+ * delivered as one batch of events. Requires the `--allow-read` flag for
+ * every path. Only supported on Linux.
+ *
+ *     for await (const events of Deno.watchFs("src")) {
+ *       console.log(events);
+ *     }
+ */
+export function watchFs(
+  paths: string | string[],
+  options: WatchFsOptions = {}
+): FsWatcher {
+  return new FsWatcherImpl(Array.isArray(paths) ? paths : [paths], options);
+}
@@ -0,0 +1,35 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+import { testPerm, assert, assertEquals } from "./test_util.ts";
+
+testPerm({ read: false }, function watchFsPermissions(): void {
+  let caughtError = false;
+  try {
+    Deno.watchFs(".");
+  } catch (err) {
+    caughtError = true;
+    assertEquals(err.kind, Deno.ErrorKind.PermissionDenied);
+    assertEquals(err.name, "PermissionDenied");
+  }
+  assert(caughtError);
+});
+
+testPerm({ read: true, write: true }, async function watchFsCreate(): Promise<
+  void
+> {
+  if (Deno.build.os !== "linux") {
+    return;
+  }
+  const dir = Deno.makeTempDirSync();
+  const watcher = Deno.watchFs(dir);
+  const filename = dir + "/file.txt";
+  Deno.writeFileSync(filename, new TextEncoder().encode("hello"));
+  const result = await watcher.next();
+  assert(!result.done);
+  const events = result.value as Deno.FsEvent[];
+  assert(events.length > 0);
+  assertEquals(events[0].kind, "create");
+  assertEquals(events[0].paths, [filename]);
+  watcher.close();
+  assert((await watcher.next()).done);
+  Deno.removeSync(dir, { recursive: true });
+});
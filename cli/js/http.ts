@@ -0,0 +1,138 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+import * as domTypes from "./dom_types.ts";
+import { EOF, Reader, Closer } from "./io.ts";
+import { read, close } from "./files.ts";
+import { Headers } from "./headers.ts";
+import { Listener, ListenerImpl } from "./net.ts";
+import { TextEncoder } from "./text_encoding.ts";
+import * as dispatch from "./dispatch.ts";
+import { sendSync, sendAsync } from "./dispatch_json.ts";
+
+/** The body of a request received by an `HttpServer`. */
+export interface HttpRequestBody extends Reader, Closer {
+  readonly rid: number;
+}
+
+/** A request received by an `HttpServer`. */
+export interface HttpRequest {
+  readonly method: string;
+  /** The path and query of the request. */
+  readonly url: string;
+  readonly headers: domTypes.Headers;
+  readonly remoteAddr: string;
+  readonly body: HttpRequestBody;
+  /** Sends the response. The request body is closed. */
+  respond(response: HttpResponse): void;
+}
+
+export interface HttpResponse {
+  /** Defaults to 200. */
+  status?: number;
+  headers?: domTypes.Headers | Array<[string, string]>;
+  /** A reader with a resource id, like a `Deno.File`, is streamed. */
+  body?: Uint8Array | string | (Reader & { rid: number });
+}
+
+/** Requests received by a listener, parsed natively. */
+export interface HttpServer extends AsyncIterator<HttpRequest> {
+  readonly rid: number;
+  /** Stops receiving requests and closes the listener. */
+  close(): void;
+  [Symbol.asyncIterator](): AsyncIterator<HttpRequest>;
+}
+
+class HttpRequestBodyImpl implements HttpRequestBody {
+  closed = false;
+
+  constructor(readonly rid: number) {}
+
+  read(p: Uint8Array): Promise<number | EOF> {
+    return read(this.rid, p);
+  }
+
+  close(): void {
+    if (!this.closed) {
+      this.closed = true;
+      close(this.rid);
+    }
+  }
+}
+
+class HttpRequestImpl implements HttpRequest {
+  constructor(
+    private requestRid: number,
+    readonly method: string,
+    readonly url: string,
+    readonly headers: domTypes.Headers,
+    readonly remoteAddr: string,
+    readonly body: HttpRequestBodyImpl
+  ) {}
+
+  respond(response: HttpResponse): void {
+    const status = response.status || 200;
+    let headerArray: Array<[string, string]> = [];
+    if (response.headers) {
+      headerArray = Array.from(new Headers(response.headers).entries());
+    }
+    let bodyRid: number | undefined;
+    let zeroCopy: Uint8Array | undefined;
+    if (typeof response.body === "string") {
+      zeroCopy = new TextEncoder().encode(response.body);
+    } else if (response.body instanceof Uint8Array) {
+      zeroCopy = response.body;
+    } else if (response.body) {
+      bodyRid = response.body.rid;
+    }
+    this.body.close();
+    sendSync(
+      dispatch.OP_HTTP_RESPOND,
+      { rid: this.requestRid, status, headers: headerArray, bodyRid },
+      zeroCopy
+    );
+  }
+}
+
+class HttpServerImpl implements HttpServer {
+  constructor(readonly rid: number, private listener: Listener) {}
+
+  async next(): Promise<IteratorResult<HttpRequest>> {
+    const res = await sendAsync(dispatch.OP_HTTP_NEXT_REQUEST, {
+      rid: this.rid
+    });
+    if (res === null) {
+      return { value: undefined, done: true };
+    }
+    const request = new HttpRequestImpl(
+      res.requestRid,
+      res.method,
+      res.url,
+      new Headers(res.headers),
+      res.remoteAddr,
+      new HttpRequestBodyImpl(res.bodyRid)
+    );
+    return { value: request, done: false };
+  }
+
+  close(): void {
+    close(this.rid);
+    this.listener.close();
+  }
+
+  [Symbol.asyncIterator](): AsyncIterator<HttpRequest> {
+    return this;
+  }
+}
+
+/** Serves HTTP/1.1 on the connections accepted by a TCP `listener`. Requests
+ * are parsed natively and the listener must not be used for anything else.
+ *
+ *     const server = Deno.serveHttp(Deno.listen({ port: 8000 }));
+ *     for await (const req of server) {
+ *       req.respond({ body: "Hello World\n" });
+ *     }
+ */
+export function serveHttp(listener: Listener): HttpServer {
+  const listenerRid = (listener as ListenerImpl).rid;
+  const rid = sendSync(dispatch.OP_HTTP_SERVE, { rid: listenerRid }) as number;
+  return new HttpServerImpl(rid, listener);
+}
@@ -0,0 +1,50 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+import { testPerm, assertEquals } from "./test_util.ts";
+
+testPerm({ net: true }, async function serveHttpRespond(): Promise<void> {
+  const server = Deno.serveHttp(Deno.listen({ port: 4506 }));
+  const serving = (async (): Promise<void> => {
+    for await (const req of server) {
+      const body = new TextDecoder().decode(await Deno.readAll(req.body));
+      req.respond({
+        status: 201,
+        headers: [["x-method", req.method]],
+        body: `${req.url} ${body}`
+      });
+    }
+  })();
+
+  const response = await fetch("http://127.0.0.1:4506/hello?a=1", {
+    method: "POST",
+    body: "world"
+  });
+  assertEquals(response.status, 201);
+  assertEquals(response.headers.get("x-method"), "POST");
+  assertEquals(await response.text(), "/hello?a=1 world");
+
+  server.close();
+  await serving;
+});
+
+testPerm(
+  { net: true, read: true },
+  async function serveHttpRespondWithFile(): Promise<void> {
+    const server = Deno.serveHttp(Deno.listen({ port: 4507 }));
+    const file = await Deno.open("cli/tests/fixture.json");
+    const serving = (async (): Promise<void> => {
+      for await (const req of server) {
+        req.respond({ body: file });
+      }
+    })();
+
+    const response = await fetch("http://127.0.0.1:4507/");
+    const json = await response.json();
+    assertEquals(json.name, "deno");
+    // The body is streamed after respond() returns, so the file can only be
+    // closed once it has been received.
+    file.close();
+
+    server.close();
+    await serving;
+  }
+);
@@ -22,6 +22,19 @@ declare namespace Deno {
     stdout: boolean;
     stderr: boolean;
   };
+  /** Puts the terminal behind `rid`, like `Deno.stdin.rid`, in raw mode or
+   * restores its previous mode. In raw mode input is available byte by byte,
+   * is not echoed and ctrl-c does not send a signal. Only supported on Unix.
+   *
+   *       Deno.setRaw(Deno.stdin.rid, true);
+   */
+  export function setRaw(rid: number, mode: boolean): void;
+  /** Returns the size of the terminal behind `rid`, like `Deno.stdout.rid`.
+   * Only supported on Unix.
+   *
+   *       const { columns, rows } = Deno.consoleSize(Deno.stdout.rid);
+   */
+  export function consoleSize(rid: number): { columns: number; rows: number };
   /** Get the hostname.
    * Requires the `--allow-env` flag.
    *
@@ -171,18 +184,27 @@ declare namespace Deno {
   // @url js/files.d.ts
 
   /** Open a file and return an instance of the `File` object
-   *  synchronously.
+   *  synchronously. `mode` is either an `OpenMode` or `OpenOptions`.
    *
    *       const file = Deno.openSync("/foo/bar.txt");
+   *       const lock = Deno.openSync("lock", { write: true, createNew: true });
    */
-  export function openSync(filename: string, mode?: OpenMode): File;
-  /** Open a file and return an instance of the `File` object.
+  export function openSync(
+    filename: string,
+    mode?: OpenMode | OpenOptions
+  ): File;
+  /** Open a file and return an instance of the `File` object. `mode` is
+   * either an `OpenMode` or `OpenOptions`.
    *
    *       (async () => {
    *         const file = await Deno.open("/foo/bar.txt");
+   *         const log = await Deno.open("log", { append: true, create: true });
    *       })();
    */
-  export function open(filename: string, mode?: OpenMode): Promise<File>;
+  export function open(
+    filename: string,
+    mode?: OpenMode | OpenOptions
+  ): Promise<File>;
   /** Read synchronously from a file ID into an array buffer.
    *
    * Return `number | EOF` for the operation.
@@ -247,6 +269,66 @@ declare namespace Deno {
     offset: number,
     whence: SeekMode
   ): Promise<void>;
+  /** Takes an advisory lock on the file ID synchronously, blocking until other
+   * holders have released it. The lock is shared unless `exclusive` is true.
+   * Only supported on Unix.
+   *
+   *       const file = Deno.openSync("/foo/bar.txt", "r+");
+   *       Deno.flockSync(file.rid, true);
+   */
+  export function flockSync(rid: number, exclusive?: boolean): void;
+  /** Takes an advisory lock on the file ID. Resolves once other holders have
+   * released it. The lock is shared unless `exclusive` is true. Only supported
+   * on Unix.
+   *
+   *       const file = await Deno.open("/foo/bar.txt", "r+");
+   *       await Deno.flock(file.rid, true);
+   */
+  export function flock(rid: number, exclusive?: boolean): Promise<void>;
+  /** Releases the lock taken with `flock()` synchronously. Closing the file
+   * releases it as well.
+   *
+   *       Deno.funlockSync(file.rid);
+   */
+  export function funlockSync(rid: number): void;
+  /** Releases the lock taken with `flock()`. Closing the file releases it as
+   * well.
+   *
+   *       await Deno.funlock(file.rid);
+   */
+  export function funlock(rid: number): Promise<void>;
+  /** Flushes the data and metadata of the file ID to disk synchronously.
+   *
+   *       Deno.fsyncSync(file.rid);
+   */
+  export function fsyncSync(rid: number): void;
+  /** Flushes the data and metadata of the file ID to disk.
+   *
+   *       await Deno.fsync(file.rid);
+   */
+  export function fsync(rid: number): Promise<void>;
+  /** Flushes the data of the file ID to disk synchronously, without metadata
+   * that is not needed to read it back, like the modification time.
+   *
+   *       Deno.fdatasyncSync(file.rid);
+   */
+  export function fdatasyncSync(rid: number): void;
+  /** Flushes the data of the file ID to disk, without metadata that is not
+   * needed to read it back, like the modification time.
+   *
+   *       await Deno.fdatasync(file.rid);
+   */
+  export function fdatasync(rid: number): Promise<void>;
+  /** Truncates or extends the file ID synchronously to `len` bytes.
+   *
+   *       Deno.ftruncateSync(file.rid, 10);
+   */
+  export function ftruncateSync(rid: number, len?: number): void;
+  /** Truncates or extends the file ID to `len` bytes.
+   *
+   *       await Deno.ftruncate(file.rid, 10);
+   */
+  export function ftruncate(rid: number, len?: number): Promise<void>;
   /** Close the file ID. */
   export function close(rid: number): void;
   /** The Deno abstraction for reading and writing files. */
@@ -299,6 +381,30 @@ declare namespace Deno {
     | "x"
     /** Read-write. Behaves like `x` and allows to read from file. */
     | "x+";
+  /** Options for `open()`, as an alternative to `OpenMode`. Opening for
+   * reading requires the `--allow-read` flag, any option that may create or
+   * change the file requires `--allow-write`.
+   */
+  export interface OpenOptions {
+    /** Open for reading. */
+    read?: boolean;
+    /** Open for writing. */
+    write?: boolean;
+    /** Open for writing, every write goes to the end of the file. */
+    append?: boolean;
+    /** Truncate an existing file to 0 bytes. Requires `write`. */
+    truncate?: boolean;
+    /** Create the file if it does not exist. Requires `write` or `append`. */
+    create?: boolean;
+    /** Create the file, failing if it already exists. Requires `write` or
+     * `append`.
+     */
+    createNew?: boolean;
+    /** Permission bits of a created file, like `0o600`, before the umask is
+     * applied. Ignored on Windows.
+     */
+    mode?: number;
+  }
 
   // @url js/buffer.d.ts
 
@@ -713,6 +819,18 @@ declare namespace Deno {
    *       assert(fileInfo.isFile());
    */
   export function statSync(filename: string): FileInfo;
+  /** Queries information on the open file ID synchronously.
+   *
+   *       const fileInfo = Deno.fstatSync(file.rid);
+   *       console.log(fileInfo.len);
+   */
+  export function fstatSync(rid: number): FileInfo;
+  /** Queries information on the open file ID.
+   *
+   *       const fileInfo = await Deno.fstat(file.rid);
+   *       console.log(fileInfo.len);
+   */
+  export function fstat(rid: number): Promise<FileInfo>;
 
   // @url js/link.d.ts
 
@@ -894,7 +1012,10 @@ declare namespace Deno {
     UnsupportedFetchScheme = 47,
     TooManyRedirects = 48,
     Diagnostic = 49,
-    JSError = 50
+    JSError = 50,
+    TypeError = 51,
+    DataCloneError = 52,
+    IntegrityCheckFailed = 53
   }
 
   // @url js/permissions.d.ts
@@ -987,6 +1108,36 @@ declare namespace Deno {
    */
   export function truncate(name: string, len?: number): Promise<void>;
 
+  // @url js/fs_events.d.ts
+
+  export interface FsEvent {
+    kind: "create" | "modify" | "remove" | "rename";
+    /** The source and, when known, the destination of a rename. */
+    paths: string[];
+  }
+  export interface FsWatcher extends AsyncIterator<FsEvent[]> {
+    readonly rid: number;
+    /** Stops watching. Pending iterations resolve as done. */
+    close(): void;
+    [Symbol.asyncIterator](): AsyncIterator<FsEvent[]>;
+  }
+  export interface WatchFsOptions {
+    /** Also watch all directories below the paths. Defaults to true. */
+    recursive?: boolean;
+  }
+  /** Watches the paths for changes. Changes arriving close together are
+   * delivered as one batch of events. Requires the `--allow-read` flag for
+   * every path. Only supported on Linux.
+   *
+   *     for await (const events of Deno.watchFs("src")) {
+   *       console.log(events);
+   *     }
+   */
+  export function watchFs(
+    paths: string | string[],
+    options?: WatchFsOptions
+  ): FsWatcher;
+
   // @url js/plugins.d.ts
 
   export interface AsyncHandler {
@@ -1019,7 +1170,7 @@ declare namespace Deno {
 
   // @url js/net.d.ts
 
-  type Transport = "tcp";
+  type Transport = "tcp" | "udp" | "unix";
   interface Addr {
     transport: Transport;
     address: string;
@@ -1037,6 +1188,22 @@ declare namespace Deno {
     addr(): Addr;
     [Symbol.asyncIterator](): AsyncIterator<Conn>;
   }
+  /** A DatagramConn is a socket for message-oriented protocols such as UDP. */
+  export interface DatagramConn {
+    /** Waits for and resolves to the next datagram. The returned tuple
+     * contains the number of bytes written into `p` and the address of the
+     * sender.
+     */
+    receive(p: Uint8Array): Promise<[number, Addr]>;
+    /** Sends the contents of `p` as a single datagram to `addr`. */
+    send(p: Uint8Array, addr: Addr): Promise<void>;
+    /** Close closes the socket. Any pending receive promises will be rejected
+     * with errors.
+     */
+    close(): void;
+    /** Return the local address of the `DatagramConn`. */
+    addr(): Addr;
+  }
   export interface Conn extends Reader, Writer, Closer {
     /** The local address of the connection. */
     localAddr: string;
@@ -1060,15 +1227,22 @@ declare namespace Deno {
     transport?: Transport;
   }
 
+  export interface UnixListenOptions {
+    transport: "unix";
+    path: string;
+  }
+
   /** Listen announces on the local transport address.
    *
    * @param options
    * @param options.port The port to connect to. (Required.)
    * @param options.hostname A literal IP address or host name that can be
    *   resolved to an IP address. If not specified, defaults to 0.0.0.0
-   * @param options.transport Defaults to "tcp". Later we plan to add "tcp4",
-   *   "tcp6", "udp", "udp4", "udp6", "ip", "ip4", "ip6", "unix", "unixgram" and
-   *   "unixpacket".
+   * @param options.transport Defaults to "tcp". "udp" binds a socket and
+   *   returns a `DatagramConn`. "unix" listens on the socket file at
+   *   `options.path` and requires read and write permission for it. Later we
+   *   plan to add "tcp4", "tcp6", "udp4", "udp6", "ip", "ip4", "ip6",
+   *   "unixgram" and "unixpacket".
    *
    * Examples:
    *
@@ -1076,8 +1250,13 @@ declare namespace Deno {
    *     listen({ hostname: "192.0.2.1", port: 80 })
    *     listen({ hostname: "[2001:db8::1]", port: 80 });
    *     listen({ hostname: "golang.org", port: 80, transport: "tcp" })
+   *     listen({ port: 8125, transport: "udp" })
+   *     listen({ path: "/tmp/app.sock", transport: "unix" })
    */
-  export function listen(options: ListenOptions): Listener;
+  export function listen(
+    options: ListenOptions & { transport: "udp" }
+  ): DatagramConn;
+  export function listen(options: ListenOptions | UnixListenOptions): Listener;
 
   export interface ListenTLSOptions {
     port: number;
@@ -1108,15 +1287,21 @@ declare namespace Deno {
     transport?: Transport;
   }
 
+  export interface UnixDialOptions {
+    transport: "unix";
+    path: string;
+  }
+
   /** Dial connects to the address on the named transport.
    *
    * @param options
    * @param options.port The port to connect to. (Required.)
    * @param options.hostname A literal IP address or host name that can be
    *   resolved to an IP address. If not specified, defaults to 127.0.0.1
-   * @param options.transport Defaults to "tcp". Later we plan to add "tcp4",
-   *   "tcp6", "udp", "udp4", "udp6", "ip", "ip4", "ip6", "unix", "unixgram" and
-   *   "unixpacket".
+   * @param options.transport Defaults to "tcp". "unix" connects to the socket
+   *   file at `options.path` and requires read and write permission for it.
+   *   Later we plan to add "tcp4", "tcp6", "udp", "udp4", "udp6", "ip", "ip4",
+   *   "ip6", "unixgram" and "unixpacket".
    *
    * Examples:
    *
@@ -1124,8 +1309,9 @@ declare namespace Deno {
    *     dial({ hostname: "192.0.2.1", port: 80 })
    *     dial({ hostname: "[2001:db8::1]", port: 80 });
    *     dial({ hostname: "golang.org", port: 80, transport: "tcp" })
+   *     dial({ path: "/var/run/docker.sock", transport: "unix" })
    */
-  export function dial(options: DialOptions): Promise<Conn>;
+  export function dial(options: DialOptions | UnixDialOptions): Promise<Conn>;
 
   export interface DialTLSOptions {
     port: number;
@@ -1138,6 +1324,52 @@ declare namespace Deno {
    */
   export function dialTLS(options: DialTLSOptions): Promise<Conn>;
 
+  // @url js/http.d.ts
+
+  /** The body of a request received by an `HttpServer`. */
+  export interface HttpRequestBody extends Reader, Closer {
+    readonly rid: number;
+  }
+
+  /** A request received by an `HttpServer`. */
+  export interface HttpRequest {
+    readonly method: string;
+    /** The path and query of the request. */
+    readonly url: string;
+    readonly headers: __domTypes.Headers;
+    readonly remoteAddr: string;
+    readonly body: HttpRequestBody;
+    /** Sends the response. The request body is closed. */
+    respond(response: HttpResponse): void;
+  }
+
+  export interface HttpResponse {
+    /** Defaults to 200. */
+    status?: number;
+    headers?: __domTypes.Headers | Array<[string, string]>;
+    /** A reader with a resource id, like a `Deno.File`, is streamed. */
+    body?: Uint8Array | string | (Reader & { rid: number });
+  }
+
+  /** Requests received by a listener, parsed natively. */
+  export interface HttpServer extends AsyncIterator<HttpRequest> {
+    readonly rid: number;
+    /** Stops receiving requests and closes the listener. */
+    close(): void;
+    [Symbol.asyncIterator](): AsyncIterator<HttpRequest>;
+  }
+
+  /** Serves HTTP/1.1 on the connections accepted by a TCP `listener`.
+   * Requests are parsed natively and the listener must not be used for
+   * anything else.
+   *
+   *     const server = Deno.serveHttp(Deno.listen({ port: 8000 }));
+   *     for await (const req of server) {
+   *       req.respond({ body: "Hello World\n" });
+   *     }
+   */
+  export function serveHttp(listener: Listener): HttpServer;
+
   // @url js/metrics.d.ts
   export interface Metrics {
     opsDispatched: number;
@@ -1183,8 +1415,11 @@ declare namespace Deno {
    *
    * "null" This stream will be ignored. This is the equivalent of attaching the
    * stream to /dev/null.
+   *
+   * "pty" The stream is attached to a pseudo-terminal, shared by all streams
+   * in this mode, whose other side is `Process.pty`. Unix only.
    */
-  type ProcessStdio = "inherit" | "piped" | "null";
+  type ProcessStdio = "inherit" | "piped" | "null" | "pty";
   export interface RunOptions {
     args: string[];
     cwd?: string;
@@ -1194,10 +1429,27 @@ declare namespace Deno {
     stdout?: ProcessStdio | number;
     stderr?: ProcessStdio | number;
     stdin?: ProcessStdio | number;
+    /** Do not inherit the environment of the parent, only pass `env`. */
+    clearEnv?: boolean;
+    /** Start the process in a new process group. Unix only. */
+    newProcessGroup?: boolean;
+    /** Start the process in a new session, which is also a new process
+     * group. Unix only.
+     */
+    newSession?: boolean;
+    /** Run the process as this user id. Unix only. */
+    uid?: number;
+    /** Run the process as this group id. Unix only. */
+    gid?: number;
+  }
+  export interface KillOptions {
+    /** Send the signal to the whole process group of the process. */
+    group?: boolean;
   }
   /** Send a signal to process under given PID. Unix only at this moment.
    * If pid is negative, the signal will be sent to the process group identified
-   * by -pid.
+   * by -pid. A signo of 0 sends no signal, but throws if the process does not
+   * exist.
    * Requires the `--allow-run` flag.
    */
   export function kill(pid: number, signo: number): void;
@@ -1207,6 +1459,8 @@ declare namespace Deno {
     readonly stdin?: WriteCloser;
     readonly stdout?: ReadCloser;
     readonly stderr?: ReadCloser;
+    /** Master side of the pseudo-terminal of "pty" stdio streams. */
+    readonly pty?: File;
     status(): Promise<ProcessStatus>;
     /** Buffer the stdout and return it as Uint8Array after EOF.
      * You must set stdout to "piped" when creating the process.
@@ -1219,7 +1473,16 @@ declare namespace Deno {
      */
     stderrOutput(): Promise<Uint8Array>;
     close(): void;
-    kill(signo: number): void;
+    /** Changes the size of the pseudo-terminal. The process receives
+     * SIGWINCH.
+     */
+    resizePty(rows: number, columns: number): void;
+    /** Sends `signo` to the process. With `options.group` it is sent to all
+     * processes in its group instead, so that grandchildren are not leaked.
+     * This requires the process to be started with `newProcessGroup` or
+     * `newSession`.
+     */
+    kill(signo: number, options?: KillOptions): void;
   }
   export interface ProcessStatus {
     success: boolean;
@@ -1238,6 +1501,10 @@ declare namespace Deno {
    * By default subprocess inherits stdio of parent process. To change that
    * `opt.stdout`, `opt.stderr` and `opt.stdin` can be specified independently -
    * they can be set to either `ProcessStdio` or `rid` of open file.
+   *
+   * On Unix the subprocess can be started in a new process group or session,
+   * with `opt.newProcessGroup` or `opt.newSession`, and as another user with
+   * `opt.uid` and `opt.gid`.
    */
   export function run(opt: RunOptions): Process;
   enum LinuxSignal {
@@ -1309,6 +1576,51 @@ declare namespace Deno {
   /** Signals numbers. This is platform dependent.
    */
   export const Signal: typeof MacOSSignal | typeof LinuxSignal;
+
+  // @url js/signals.d.ts
+
+  /** Stream of deliveries of a signal. Call `dispose()` to stop listening. */
+  export class SignalStream implements AsyncIterator<void>, PromiseLike<void> {
+    constructor(signo: number);
+    then<T = void, S = void>(
+      f: (v: void) => T | Promise<T>,
+      g?: (v: Error) => S | Promise<S>
+    ): Promise<T | S>;
+    next(): Promise<IteratorResult<void>>;
+    [Symbol.asyncIterator](): AsyncIterator<void>;
+    /** Stops listening. Pending iterations resolve as done. */
+    dispose(): void;
+  }
+  /** Returns the stream of deliveries of the signal `signo`. Unix only.
+   *
+   * Once a signal is listened for, its default action, like terminating the
+   * process on SIGINT, no longer happens even after the stream is disposed.
+   *
+   * The stream is an async iterator and can also be awaited for the next
+   * delivery:
+   *
+   *     for await (const _ of Deno.signal(Deno.Signal.SIGTERM)) {
+   *       console.log("got SIGTERM!");
+   *     }
+   *
+   *     await Deno.signal(Deno.Signal.SIGTERM);
+   *     console.log("got SIGTERM!");
+   */
+  export function signal(signo: number): SignalStream;
+  /** Shorthands for `Deno.signal()` of common signals. */
+  export const signals: {
+    alarm: () => SignalStream;
+    child: () => SignalStream;
+    hangup: () => SignalStream;
+    interrupt: () => SignalStream;
+    io: () => SignalStream;
+    pipe: () => SignalStream;
+    quit: () => SignalStream;
+    terminate: () => SignalStream;
+    userDefined1: () => SignalStream;
+    userDefined2: () => SignalStream;
+    windowChange: () => SignalStream;
+  };
   export {};
 
   // @url js/console.d.ts
@@ -1874,8 +2186,18 @@ declare namespace __domTypes {
     | "error"
     | "opaque"
     | "opaqueredirect";
+  /** A reader backed by a resource, like `Deno.File` or a child process' stdout.
+   */
+  export interface ResourceReader {
+    readonly rid: number;
+    read(p: Uint8Array): Promise<number | symbol>;
+  }
   export interface RequestInit {
-    body?: BodyInit | null;
+    /** A body to send with the request. Besides the standard body types,
+     * `fetch()` accepts a reader with a resource id, like a `Deno.File`, which
+     * is streamed rather than buffered. `Request` does not accept one.
+     */
+    body?: BodyInit | ResourceReader | null;
     cache?: RequestCache;
     credentials?: RequestCredentials;
     headers?: HeadersInit;
@@ -1886,7 +2208,14 @@ declare namespace __domTypes {
     redirect?: RequestRedirect;
     referrer?: string;
     referrerPolicy?: ReferrerPolicy;
+    /** Aborting the signal cancels the request while it waits for the response
+     * headers. Reading the response body is not covered.
+     */
     signal?: AbortSignal | null;
+    /** Milliseconds `fetch()` waits for the response headers before it fails
+     * with `ErrorKind.TimedOut`. Reading the response body is not covered.
+     */
+    timeout?: number;
     window?: any;
   }
   export interface ResponseInit {
@@ -2479,7 +2808,12 @@ declare namespace __fetch {
     readonly ok: boolean;
     clone(): __domTypes.Response;
   }
-  /** Fetch a resource from the network. */
+  /** Fetch a resource from the network.
+   *
+   * Besides the standard body types, `init.body` may be a reader with a
+   * resource id, like a `Deno.File`, which is streamed rather than buffered.
+   * Aborting `init.signal` cancels the request.
+   */
   export function fetch(
     input: __domTypes.Request | string,
     init?: __domTypes.RequestInit
@@ -2702,12 +3036,29 @@ declare namespace __workers {
     closed: Promise<void>;
   }
   export interface WorkerOptions {}
+  /** Permissions of a worker, which can never exceed those of its parent.
+   * `true` gives the worker the permission as the parent has it, a list of
+   * paths, hosts, environment variables or programs gives access to those
+   * only, and `false` or leaving it out denies it.
+   */
+  export interface WorkerPermissions {
+    read?: boolean | string[];
+    write?: boolean | string[];
+    net?: boolean | string[];
+    env?: boolean | string[];
+    run?: boolean | string[];
+    plugin?: boolean;
+    hrtime?: boolean;
+  }
   /** Extended Deno Worker initialization options.
    * `noDenoNamespace` hides global `window.Deno` namespace for
    * spawned worker and nested workers spawned by it (default: false).
+   * `permissions` gives the worker its own permissions instead of sharing
+   * those of the parent.
    */
   export interface DenoWorkerOptions extends WorkerOptions {
     noDenoNamespace?: boolean;
+    permissions?: WorkerPermissions;
   }
   export class WorkerImpl implements Worker {
     private readonly id;
@@ -5,9 +5,9 @@ import { read, write, close } from "./files.ts";
 import * as dispatch from "./dispatch.ts";
 import { sendSync, sendAsync } from "./dispatch_json.ts";
 
-export type Transport = "tcp";
+export type Transport = "tcp" | "udp" | "unix";
 // TODO support other types:
-// export type Transport = "tcp" | "tcp4" | "tcp6" | "unix" | "unixpacket";
+// export type Transport = "tcp" | "tcp4" | "tcp6" | "unixpacket";
 
 // TODO(ry) Replace 'address' with 'hostname' and 'port', similar to DialOptions
 // and ListenOptions.
@@ -125,6 +125,63 @@ export class ListenerImpl implements Listener {
   }
 }
 
+/** A DatagramConn is a socket for message-oriented protocols such as UDP. */
+export interface DatagramConn {
+  /** Waits for and resolves to the next datagram. The returned tuple contains
+   * the number of bytes written into `p` and the address of the sender.
+   */
+  receive(p: Uint8Array): Promise<[number, Addr]>;
+
+  /** Sends the contents of `p` as a single datagram to `addr`. */
+  send(p: Uint8Array, addr: Addr): Promise<void>;
+
+  /** Close closes the socket. Any pending receive promises will be rejected
+   * with errors.
+   */
+  close(): void;
+
+  /** Return the local address of the `DatagramConn`. */
+  addr(): Addr;
+}
+
+/** Splits an address of the form "host:port" or "[ipv6]:port". */
+function splitAddress(address: string): [string, number] {
+  const i = address.lastIndexOf(":");
+  if (i < 0) {
+    throw new TypeError(`Invalid address: ${address}`);
+  }
+  return [address.slice(0, i), Number(address.slice(i + 1))];
+}
+
+export class DatagramImpl implements DatagramConn {
+  constructor(
+    readonly rid: number,
+    private transport: Transport,
+    private localAddr: string
+  ) {}
+
+  async receive(p: Uint8Array): Promise<[number, Addr]> {
+    const res = await sendAsync(dispatch.OP_RECV_FROM, { rid: this.rid }, p);
+    return [res.size, { transport: this.transport, address: res.remoteAddr }];
+  }
+
+  async send(p: Uint8Array, addr: Addr): Promise<void> {
+    const [hostname, port] = splitAddress(addr.address);
+    await sendAsync(dispatch.OP_SEND_TO, { rid: this.rid, hostname, port }, p);
+  }
+
+  close(): void {
+    close(this.rid);
+  }
+
+  addr(): Addr {
+    return {
+      transport: this.transport,
+      address: this.localAddr
+    };
+  }
+}
+
 export interface Conn extends Reader, Writer, Closer {
   /** The local address of the connection. */
   localAddr: string;
@@ -148,15 +205,22 @@ export interface ListenOptions {
   transport?: Transport;
 }
 
+export interface UnixListenOptions {
+  transport: "unix";
+  path: string;
+}
+
 /** Listen announces on the local transport address.
  *
  * @param options
  * @param options.port The port to connect to. (Required.)
  * @param options.hostname A literal IP address or host name that can be
  *   resolved to an IP address. If not specified, defaults to 0.0.0.0
- * @param options.transport Defaults to "tcp". Later we plan to add "tcp4",
- *   "tcp6", "udp", "udp4", "udp6", "ip", "ip4", "ip6", "unix", "unixgram" and
- *   "unixpacket".
+ * @param options.transport Defaults to "tcp". "udp" binds a socket and
+ *   returns a `DatagramConn`. "unix" listens on the socket file at
+ *   `options.path` and requires read and write permission for it. Later we
+ *   plan to add "tcp4", "tcp6", "udp4", "udp6", "ip", "ip4", "ip6",
+ *   "unixgram" and "unixpacket".
  *
  * Examples:
  *
@@ -164,8 +228,20 @@ export interface ListenOptions {
  *     listen({ hostname: "192.0.2.1", port: 80 })
  *     listen({ hostname: "[2001:db8::1]", port: 80 });
  *     listen({ hostname: "golang.org", port: 80, transport: "tcp" })
+ *     listen({ port: 8125, transport: "udp" })
+ *     listen({ path: "/tmp/app.sock", transport: "unix" })
  */
-export function listen(options: ListenOptions): Listener {
+export function listen(
+  options: ListenOptions & { transport: "udp" }
+): DatagramConn;
+export function listen(options: ListenOptions | UnixListenOptions): Listener;
+export function listen(
+  options: ListenOptions | UnixListenOptions
+): Listener | DatagramConn {
+  if (options.transport === "unix") {
+    const res = sendSync(dispatch.OP_LISTEN, options);
+    return new ListenerImpl(res.rid, options.transport, res.localAddr);
+  }
   const hostname = options.hostname || "0.0.0.0";
   const transport = options.transport || "tcp";
   const res = sendSync(dispatch.OP_LISTEN, {
@@ -173,6 +249,9 @@ export function listen(options: ListenOptions): Listener {
     port: options.port,
     transport
   });
+  if (transport === "udp") {
+    return new DatagramImpl(res.rid, transport, res.localAddr);
+  }
   return new ListenerImpl(res.rid, transport, res.localAddr);
 }
 
@@ -182,15 +261,21 @@ export interface DialOptions {
   transport?: Transport;
 }
 
+export interface UnixDialOptions {
+  transport: "unix";
+  path: string;
+}
+
 /** Dial connects to the address on the named transport.
  *
  * @param options
  * @param options.port The port to connect to. (Required.)
  * @param options.hostname A literal IP address or host name that can be
  *   resolved to an IP address. If not specified, defaults to 127.0.0.1
- * @param options.transport Defaults to "tcp". Later we plan to add "tcp4",
- *   "tcp6", "udp", "udp4", "udp6", "ip", "ip4", "ip6", "unix", "unixgram" and
- *   "unixpacket".
+ * @param options.transport Defaults to "tcp". "unix" connects to the socket
+ *   file at `options.path` and requires read and write permission for it.
+ *   Later we plan to add "tcp4", "tcp6", "udp", "udp4", "udp6", "ip", "ip4",
+ *   "ip6", "unixgram" and "unixpacket".
  *
  * Examples:
  *
@@ -198,8 +283,15 @@ export interface DialOptions {
  *     dial({ hostname: "192.0.2.1", port: 80 })
  *     dial({ hostname: "[2001:db8::1]", port: 80 });
  *     dial({ hostname: "golang.org", port: 80, transport: "tcp" })
+ *     dial({ path: "/var/run/docker.sock", transport: "unix" })
  */
-export async function dial(options: DialOptions): Promise<Conn> {
+export async function dial(
+  options: DialOptions | UnixDialOptions
+): Promise<Conn> {
+  if (options.transport === "unix") {
+    const res = await sendAsync(dispatch.OP_DIAL, options);
+    return new ConnImpl(res.rid, res.remoteAddr!, res.localAddr!);
+  }
   const res = await sendAsync(dispatch.OP_DIAL, {
     hostname: options.hostname || "127.0.0.1",
     port: options.port,
@@ -1,5 +1,5 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
-import { testPerm, assert, assertEquals } from "./test_util.ts";
+import { test, testPerm, assert, assertEquals } from "./test_util.ts";
 
 testPerm({ net: true }, function netListenClose(): void {
   const listener = Deno.listen({ hostname: "127.0.0.1", port: 4500 });
@@ -77,6 +77,100 @@ testPerm({ net: true }, async function netDialListen(): Promise<void> {
   conn.close();
 });
 
+testPerm({ net: true }, async function netUdpSendReceive(): Promise<void> {
+  const alice = Deno.listen({
+    hostname: "127.0.0.1",
+    port: 4503,
+    transport: "udp"
+  });
+  assertEquals(alice.addr().transport, "udp");
+  assertEquals(alice.addr().address, "127.0.0.1:4503");
+  const bob = Deno.listen({
+    hostname: "127.0.0.1",
+    port: 4504,
+    transport: "udp"
+  });
+  const sent = new Uint8Array([1, 2, 3]);
+  await alice.send(sent, bob.addr());
+  const buf = new Uint8Array(1024);
+  const [size, remote] = await bob.receive(buf);
+  assertEquals(3, size);
+  assertEquals(1, buf[0]);
+  assertEquals(2, buf[1]);
+  assertEquals(3, buf[2]);
+  assertEquals(remote.transport, "udp");
+  assertEquals(remote.address, "127.0.0.1:4503");
+  alice.close();
+  bob.close();
+});
+
+testPerm({ net: true }, async function netUdpCloseWhileReceiving(): Promise<
+  void
+> {
+  const socket = Deno.listen({
+    hostname: "127.0.0.1",
+    port: 4509,
+    transport: "udp"
+  });
+  const receiving = socket.receive(new Uint8Array(1024));
+  // Let the receive op start waiting before the socket is closed.
+  await new Promise((resolve): number => setTimeout(resolve, 10));
+  socket.close();
+  let err;
+  try {
+    await receiving;
+  } catch (e) {
+    err = e;
+  }
+  assert(!!err);
+  assertEquals(err.message, "Socket has been closed");
+});
+
+testPerm(
+  { read: true, write: true },
+  async function netUnixDialListen(): Promise<void> {
+    if (Deno.build.os === "win") {
+      return;
+    }
+    const path = (await Deno.makeTempDir()) + "/test.sock";
+    const listener = Deno.listen({ path, transport: "unix" });
+    assertEquals(listener.addr().transport, "unix");
+    assertEquals(listener.addr().address, path);
+    listener.accept().then(
+      async (conn): Promise<void> => {
+        assertEquals(conn.localAddr, path);
+        await conn.write(new Uint8Array([1, 2, 3]));
+        conn.close();
+      }
+    );
+    const conn = await Deno.dial({ path, transport: "unix" });
+    assertEquals(conn.remoteAddr, path);
+    const buf = new Uint8Array(1024);
+    const readResult = await conn.read(buf);
+    assertEquals(3, readResult);
+    assertEquals(1, buf[0]);
+    assertEquals(2, buf[1]);
+    assertEquals(3, buf[2]);
+    assertEquals(Deno.EOF, await conn.read(buf));
+    listener.close();
+    conn.close();
+  }
+);
+
+test(function netUnixListenPerm(): void {
+  if (Deno.build.os === "win") {
+    return;
+  }
+  let err;
+  try {
+    Deno.listen({ path: "/tmp/test.sock", transport: "unix" });
+  } catch (e) {
+    err = e;
+  }
+  assert(!!err);
+  assertEquals(err.kind, Deno.ErrorKind.PermissionDenied);
+});
+
 testPerm({ net: true }, async function netListenCloseWhileIterating(): Promise<
   void
 > {
@@ -89,6 +183,25 @@ testPerm({ net: true }, async function netListenCloseWhileIterating(): Promise<
   assertEquals(await nextAfterClosing, { value: undefined, done: true });
 });
 
+testPerm(
+  { read: true, write: true },
+  async function netUnixListenCloseWhileIterating(): Promise<void> {
+    if (Deno.build.os === "win") {
+      return;
+    }
+    const path = (await Deno.makeTempDir()) + "/test.sock";
+    const listener = Deno.listen({ path, transport: "unix" });
+    const nextWhileClosing = listener[Symbol.asyncIterator]().next();
+    // Let the accept op start waiting before the listener is closed.
+    await new Promise((resolve): number => setTimeout(resolve, 10));
+    listener.close();
+    assertEquals(await nextWhileClosing, { value: undefined, done: true });
+
+    const nextAfterClosing = listener[Symbol.asyncIterator]().next();
+    assertEquals(await nextAfterClosing, { value: undefined, done: true });
+  }
+);
+
 /* TODO(ry) Re-enable this test.
 testPerm({ net: true }, async function netListenAsyncIterator(): Promise<void> {
   const listener = Deno.listen(":4500");
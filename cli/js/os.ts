@@ -15,6 +15,25 @@ export function isTTY(): { stdin: boolean; stdout: boolean; stderr: boolean } {
   return sendSync(dispatch.OP_IS_TTY);
 }
 
+/** Puts the terminal behind `rid`, like `Deno.stdin.rid`, in raw mode or
+ * restores its previous mode. In raw mode input is available byte by byte, is
+ * not echoed and ctrl-c does not send a signal. Only supported on Unix.
+ *
+ *       Deno.setRaw(Deno.stdin.rid, true);
+ */
+export function setRaw(rid: number, mode: boolean): void {
+  sendSync(dispatch.OP_SET_RAW, { rid, mode });
+}
+
+/** Returns the size of the terminal behind `rid`, like `Deno.stdout.rid`.
+ * Only supported on Unix.
+ *
+ *       const { columns, rows } = Deno.consoleSize(Deno.stdout.rid);
+ */
+export function consoleSize(rid: number): { columns: number; rows: number } {
+  return sendSync(dispatch.OP_CONSOLE_SIZE, { rid });
+}
+
 /** Get the hostname.
  * Requires the `--allow-env` flag.
  *
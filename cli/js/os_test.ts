@@ -115,6 +115,18 @@ test(function osIsTTYSmoke(): void {
   console.log(Deno.isTTY());
 });
 
+testPerm({ read: true }, function consoleSizeNotTerminal(): void {
+  const file = Deno.openSync("cli/tests/hello.txt");
+  let err;
+  try {
+    Deno.consoleSize(file.rid);
+  } catch (e) {
+    err = e;
+  }
+  file.close();
+  assert(err instanceof Deno.DenoError);
+});
+
 testPerm({ env: true }, function homeDir(): void {
   assertNotEquals(Deno.homeDir(), "");
 });
@@ -17,8 +17,11 @@ import { build } from "./build.ts";
  *
  * "null" This stream will be ignored. This is the equivalent of attaching the
  * stream to /dev/null.
+ *
+ * "pty" The stream is attached to a pseudo-terminal, shared by all streams in
+ * this mode, whose other side is `Process.pty`. Unix only.
  */
-export type ProcessStdio = "inherit" | "piped" | "null";
+export type ProcessStdio = "inherit" | "piped" | "null" | "pty";
 
 // TODO Maybe extend VSCode's 'CommandOptions'?
 // See https://code.visualstudio.com/docs/editor/tasks-appendix#_schema-for-tasksjson
@@ -29,6 +32,23 @@ export interface RunOptions {
   stdout?: ProcessStdio | number;
   stderr?: ProcessStdio | number;
   stdin?: ProcessStdio | number;
+  /** Do not inherit the environment of the parent, only pass `env`. */
+  clearEnv?: boolean;
+  /** Start the process in a new process group. Unix only. */
+  newProcessGroup?: boolean;
+  /** Start the process in a new session, which is also a new process group.
+   * Unix only.
+   */
+  newSession?: boolean;
+  /** Run the process as this user id. Unix only. */
+  uid?: number;
+  /** Run the process as this group id. Unix only. */
+  gid?: number;
+}
+
+export interface KillOptions {
+  /** Send the signal to the whole process group of the process. */
+  group?: boolean;
 }
 
 interface RunStatusResponse {
@@ -53,7 +73,8 @@ async function runStatus(rid: number): Promise<ProcessStatus> {
 
 /** Send a signal to process under given PID. Unix only at this moment.
  * If pid is negative, the signal will be sent to the process group identified
- * by -pid.
+ * by -pid. A signo of 0 sends no signal, but throws if the process does not
+ * exist.
  * Requires the `--allow-run` flag.
  */
 export function kill(pid: number, signo: number): void {
@@ -66,10 +87,14 @@ export class Process {
   readonly stdin?: WriteCloser;
   readonly stdout?: ReadCloser;
   readonly stderr?: ReadCloser;
+  /** Master side of the pseudo-terminal of "pty" stdio streams. */
+  readonly pty?: File;
+  private readonly groupLeader: boolean;
 
   // @internal
-  constructor(res: RunResponse) {
+  constructor(res: RunResponse, groupLeader = false) {
     this.rid = res.rid;
+    this.groupLeader = groupLeader;
     this.pid = res.pid;
 
     if (res.stdinRid && res.stdinRid > 0) {
@@ -83,6 +108,10 @@ export class Process {
     if (res.stderrRid && res.stderrRid > 0) {
       this.stderr = new File(res.stderrRid);
     }
+
+    if (res.ptyRid != null) {
+      this.pty = new File(res.ptyRid);
+    }
   }
 
   async status(): Promise<ProcessStatus> {
@@ -123,8 +152,31 @@ export class Process {
     close(this.rid);
   }
 
-  kill(signo: number): void {
-    kill(this.pid, signo);
+  /** Changes the size of the pseudo-terminal. The process receives SIGWINCH.
+   */
+  resizePty(rows: number, columns: number): void {
+    if (!this.pty) {
+      throw new Error("Process.resizePty: pty is undefined");
+    }
+    sendSync(dispatch.OP_PTY_RESIZE, { rid: this.pty.rid, rows, columns });
+  }
+
+  /** Sends `signo` to the process. With `options.group` it is sent to all
+   * processes in its group instead, so that grandchildren are not leaked.
+   * This requires the process to be started with `newProcessGroup` or
+   * `newSession`.
+   */
+  kill(signo: number, options: KillOptions = {}): void {
+    if (options.group) {
+      if (!this.groupLeader) {
+        throw new Error(
+          "Process.kill: process was not started in a new process group"
+        );
+      }
+      kill(-this.pid, signo);
+    } else {
+      kill(this.pid, signo);
+    }
   }
 }
 
@@ -140,6 +192,7 @@ function stdioMap(s: string): string {
     case "inherit":
     case "piped":
     case "null":
+    case "pty":
       return s;
     default:
       return unreachable();
@@ -156,6 +209,7 @@ interface RunResponse {
   stdinRid: number | null;
   stdoutRid: number | null;
   stderrRid: number | null;
+  ptyRid: number | null;
 }
 /**
  * Spawns new subprocess.
@@ -169,6 +223,10 @@ interface RunResponse {
  * By default subprocess inherits stdio of parent process. To change that
  * `opt.stdout`, `opt.stderr` and `opt.stdin` can be specified independently -
  * they can be set to either `ProcessStdio` or `rid` of open file.
+ *
+ * On Unix the subprocess can be started in a new process group or session,
+ * with `opt.newProcessGroup` or `opt.newSession`, and as another user with
+ * `opt.uid` and `opt.gid`.
  */
 export function run(opt: RunOptions): Process {
   assert(opt.args.length > 0);
@@ -217,15 +275,25 @@ export function run(opt: RunOptions): Process {
     stderr,
     stdinRid,
     stdoutRid,
-    stderrRid
+    stderrRid,
+    clearEnv: opt.clearEnv,
+    newProcessGroup: opt.newProcessGroup,
+    newSession: opt.newSession,
+    uid: opt.uid,
+    gid: opt.gid
   };
 
   const res = sendSync(dispatch.OP_RUN, req) as RunResponse;
-  return new Process(res);
+  // A child attached to a pseudo-terminal leads a new session.
+  const groupLeader = [stdin, stdout, stderr].includes("pty");
+  return new Process(
+    res,
+    groupLeader || !!(opt.newProcessGroup || opt.newSession)
+  );
 }
 
 // From `kill -l`
-enum LinuxSignal {
+export enum LinuxSignal {
   SIGHUP = 1,
   SIGINT = 2,
   SIGQUIT = 3,
@@ -260,7 +328,7 @@ enum LinuxSignal {
 }
 
 // From `kill -l`
-enum MacOSSignal {
+export enum MacOSSignal {
   SIGHUP = 1,
   SIGINT = 2,
   SIGQUIT = 3,
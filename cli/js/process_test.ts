@@ -292,6 +292,28 @@ testPerm({ run: true }, async function runEnv(): Promise<void> {
   p.close();
 });
 
+testPerm({ run: true, env: true }, async function runClearEnv(): Promise<
+  void
+> {
+  const p = run({
+    args: [
+      "python",
+      "-c",
+      "import os, sys; sys.stdout.write(','.join(sorted(os.environ.keys())))"
+    ],
+    env: {
+      PATH: Deno.env().PATH,
+      FOO: "0123"
+    },
+    clearEnv: true,
+    stdout: "piped"
+  });
+  const output = await p.output();
+  const s = new TextDecoder().decode(output);
+  assertEquals(s, "FOO,PATH");
+  p.close();
+});
+
 testPerm({ run: true }, async function runClose(): Promise<void> {
   const p = run({
     args: [
@@ -374,4 +396,87 @@ if (Deno.build.os !== "win") {
 
     p.close();
   });
+
+  testPerm({ run: true }, async function runPty(): Promise<void> {
+    const p = run({
+      args: [
+        "python",
+        "-c",
+        "import sys; sys.stdout.write(str(sys.stdout.isatty()))"
+      ],
+      stdout: "pty",
+      stdin: "pty"
+    });
+    assert(!p.stdout);
+    p.resizePty(40, 120);
+    const status = await p.status();
+    assertEquals(status.success, true);
+    const output = await Deno.readAll(p.pty);
+    assertEquals(new TextDecoder().decode(output), "True");
+    p.pty.close();
+    p.close();
+  });
+
+  testPerm({ run: true }, async function killGroup(): Promise<void> {
+    const p = run({
+      args: [
+        "python",
+        "-c",
+        "import subprocess, sys, time; " +
+          "child = subprocess.Popen(['sleep', '10000']); " +
+          "print(child.pid); sys.stdout.flush(); time.sleep(10000)"
+      ],
+      newProcessGroup: true,
+      stdout: "piped"
+    });
+
+    // Only signal the group once the grandchild is running.
+    const data = new Uint8Array(32);
+    const r = await p.stdout.read(data);
+    if (r === Deno.EOF) {
+      throw new Error("p.stdout.read(...) should not be EOF");
+    }
+    const grandchild = Number(new TextDecoder().decode(data.subarray(0, r)));
+    assert(grandchild > 0);
+    kill(grandchild, 0);
+
+    p.kill(Deno.Signal.SIGKILL, { group: true });
+    const status = await p.status();
+    assertEquals(status.success, false);
+    assertEquals(status.signal, Deno.Signal.SIGKILL);
+
+    // The orphaned grandchild is gone once it has been reaped.
+    let err;
+    for (let i = 0; i < 100 && !err; i++) {
+      try {
+        kill(grandchild, 0);
+        await new Promise((resolve): number => setTimeout(resolve, 20));
+      } catch (e) {
+        err = e;
+      }
+    }
+    assert(!!err);
+    p.stdout.close();
+    p.close();
+  });
+
+  testPerm({ run: true }, async function killGroupNotLeader(): Promise<
+    void
+  > {
+    const p = run({
+      args: ["python", "-c", "from time import sleep; sleep(10000)"]
+    });
+
+    let err;
+    try {
+      p.kill(Deno.Signal.SIGKILL, { group: true });
+    } catch (e) {
+      err = e;
+    }
+    assert(!!err);
+
+    p.kill(Deno.Signal.SIGKILL);
+    await p.status();
+    p.close();
+  });
 }
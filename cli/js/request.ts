@@ -3,6 +3,7 @@ import * as headers from "./headers.ts";
 import * as body from "./body.ts";
 import * as domTypes from "./dom_types.ts";
 import * as streams from "./streams/mod.ts";
+import { isResourceReader } from "./util.ts";
 
 const { Headers } = headers;
 const { ReadableStream } = streams;
@@ -52,6 +53,9 @@ export class Request extends body.Body implements domTypes.Request {
 
     // prefer body from init
     if (init.body) {
+      if (isResourceReader(init.body)) {
+        throw TypeError("A resource body can only be passed to fetch()");
+      }
       b = init.body;
     } else if (input instanceof Request && input._bodySource) {
       if (input.bodyUsed) {
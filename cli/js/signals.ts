@@ -0,0 +1,116 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+import { Signal, LinuxSignal, MacOSSignal } from "./process.ts";
+import * as dispatch from "./dispatch.ts";
+import { sendSync, sendAsync } from "./dispatch_json.ts";
+import { build } from "./build.ts";
+
+/** Returns the stream of deliveries of the signal `signo`. Unix only.
+ *
+ * Once a signal is listened for, its default action, like terminating the
+ * process on SIGINT, no longer happens even after the stream is disposed.
+ *
+ * The stream is an async iterator and can also be awaited for the next
+ * delivery:
+ *
+ *     for await (const _ of Deno.signal(Deno.Signal.SIGTERM)) {
+ *       console.log("got SIGTERM!");
+ *     }
+ *
+ *     await Deno.signal(Deno.Signal.SIGTERM);
+ *     console.log("got SIGTERM!");
+ */
+export function signal(signo: number): SignalStream {
+  if (build.os === "win") {
+    throw new Error("Deno.signal() is not implemented for Windows");
+  }
+  return new SignalStream(signo);
+}
+
+function signalNumbers(): typeof LinuxSignal | typeof MacOSSignal {
+  return Signal as typeof LinuxSignal | typeof MacOSSignal;
+}
+
+/** Shorthands for `Deno.signal()` of common signals. */
+export const signals = {
+  alarm(): SignalStream {
+    return signal(signalNumbers().SIGALRM);
+  },
+  child(): SignalStream {
+    return signal(signalNumbers().SIGCHLD);
+  },
+  hangup(): SignalStream {
+    return signal(signalNumbers().SIGHUP);
+  },
+  interrupt(): SignalStream {
+    return signal(signalNumbers().SIGINT);
+  },
+  io(): SignalStream {
+    return signal(signalNumbers().SIGIO);
+  },
+  pipe(): SignalStream {
+    return signal(signalNumbers().SIGPIPE);
+  },
+  quit(): SignalStream {
+    return signal(signalNumbers().SIGQUIT);
+  },
+  terminate(): SignalStream {
+    return signal(signalNumbers().SIGTERM);
+  },
+  userDefined1(): SignalStream {
+    return signal(signalNumbers().SIGUSR1);
+  },
+  userDefined2(): SignalStream {
+    return signal(signalNumbers().SIGUSR2);
+  },
+  windowChange(): SignalStream {
+    return signal(signalNumbers().SIGWINCH);
+  }
+};
+
+/** Stream of deliveries of a signal. Call `dispose()` to stop listening. */
+export class SignalStream implements AsyncIterator<void>, PromiseLike<void> {
+  private rid: number;
+  /** Resolves to `true` once the stream is disposed. */
+  private pollingPromise: Promise<boolean> = Promise.resolve(false);
+  private disposed = false;
+
+  constructor(signo: number) {
+    this.rid = sendSync(dispatch.OP_SIGNAL_BIND, { signo }).rid;
+    this.loop();
+  }
+
+  private async pollSignal(): Promise<boolean> {
+    const res = await sendAsync(dispatch.OP_SIGNAL_POLL, { rid: this.rid });
+    return res.done;
+  }
+
+  private async loop(): Promise<void> {
+    do {
+      this.pollingPromise = this.pollSignal();
+    } while (!(await this.pollingPromise) && !this.disposed);
+  }
+
+  then<T = void, S = void>(
+    f: (v: void) => T | Promise<T>,
+    g?: (v: Error) => S | Promise<S>
+  ): Promise<T | S> {
+    return this.pollingPromise.then((_): void => {}).then(f, g);
+  }
+
+  async next(): Promise<IteratorResult<void>> {
+    return { done: await this.pollingPromise, value: undefined };
+  }
+
+  [Symbol.asyncIterator](): AsyncIterator<void> {
+    return this;
+  }
+
+  /** Stops listening. Pending iterations resolve as done. */
+  dispose(): void {
+    if (this.disposed) {
+      throw new Error("The stream has already been disposed.");
+    }
+    this.disposed = true;
+    sendSync(dispatch.OP_SIGNAL_UNBIND, { rid: this.rid });
+  }
+}
@@ -0,0 +1,48 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+import { test, testPerm, assert, assertEquals } from "./test_util.ts";
+
+if (Deno.build.os === "win") {
+  test(function signalNotImplemented(): void {
+    let err;
+    try {
+      Deno.signal(1);
+    } catch (e) {
+      err = e;
+    }
+    assert(!!err);
+  });
+} else {
+  testPerm({ run: true }, async function signalStreamAwait(): Promise<void> {
+    const sig = Deno.signal(Deno.Signal.SIGUSR1);
+    setTimeout((): void => {
+      Deno.kill(Deno.pid, Deno.Signal.SIGUSR1);
+    }, 20);
+    await sig;
+    sig.dispose();
+  });
+
+  testPerm({ run: true }, async function signalStreamIterate(): Promise<
+    void
+  > {
+    const sig = Deno.signals.userDefined2();
+    let count = 0;
+    const t = setInterval((): void => {
+      Deno.kill(Deno.pid, Deno.Signal.SIGUSR2);
+    }, 20);
+    for await (const _ of sig) {
+      count++;
+      if (count === 3) {
+        sig.dispose();
+      }
+    }
+    clearInterval(t);
+    assertEquals(count, 3);
+  });
+
+  test(async function signalStreamDispose(): Promise<void> {
+    const sig = Deno.signal(Deno.Signal.SIGUSR1);
+    const next = sig.next();
+    sig.dispose();
+    assertEquals((await next).done, true);
+  });
+}
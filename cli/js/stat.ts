@@ -44,6 +44,26 @@ export function lstatSync(filename: string): FileInfo {
   return new FileInfoImpl(res);
 }
 
+/** Queries information on the open file ID synchronously.
+ *
+ *       const fileInfo = Deno.fstatSync(file.rid);
+ *       console.log(fileInfo.len);
+ */
+export function fstatSync(rid: number): FileInfo {
+  const res = sendSync(dispatch.OP_FSTAT, { rid }) as StatResponse;
+  return new FileInfoImpl(res);
+}
+
+/** Queries information on the open file ID.
+ *
+ *       const fileInfo = await Deno.fstat(file.rid);
+ *       console.log(fileInfo.len);
+ */
+export async function fstat(rid: number): Promise<FileInfo> {
+  const res = (await sendAsync(dispatch.OP_FSTAT, { rid })) as StatResponse;
+  return new FileInfoImpl(res);
+}
+
 /** Queries the file system for information on the path provided. `stat` Will
  * always follow symlinks.
  *
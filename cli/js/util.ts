@@ -1,5 +1,6 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 import { TypedArray } from "./types.ts";
+import { ResourceReader } from "./dom_types.ts";
 import { window } from "./window.ts";
 
 let logDebug = false;
@@ -114,6 +115,17 @@ export function isTypedArray(x: unknown): x is TypedArray {
   return x instanceof TypedArrayConstructor;
 }
 
+// Returns whether x is a reader backed by a resource, like `Deno.File`.
+// @internal
+export function isResourceReader(x: unknown): x is ResourceReader {
+  return (
+    typeof x === "object" &&
+    x !== null &&
+    typeof (x as ResourceReader).rid === "number" &&
+    typeof (x as ResourceReader).read === "function"
+  );
+}
+
 // Returns whether o is an object, not null, and not a function.
 // @internal
 export function isObject(o: unknown): o is object {
@@ -25,13 +25,15 @@ function createWorker(
   specifier: string,
   includeDenoNamespace: boolean,
   hasSourceCode: boolean,
-  sourceCode: Uint8Array
+  sourceCode: Uint8Array,
+  permissions?: WorkerPermissions
 ): number {
   return sendSync(dispatch.OP_CREATE_WORKER, {
     specifier,
     includeDenoNamespace,
     hasSourceCode,
-    sourceCode: new TextDecoder().decode(sourceCode)
+    sourceCode: new TextDecoder().decode(sourceCode),
+    permissions
   });
 }
 
@@ -114,12 +116,30 @@ export interface Worker {
 // eslint-disable-next-line @typescript-eslint/no-empty-interface
 export interface WorkerOptions {}
 
+/** Permissions of a worker, which can never exceed those of its parent.
+ * `true` gives the worker the permission as the parent has it, a list of
+ * paths, hosts, environment variables or programs gives access to those
+ * only, and `false` or leaving it out denies it.
+ */
+export interface WorkerPermissions {
+  read?: boolean | string[];
+  write?: boolean | string[];
+  net?: boolean | string[];
+  env?: boolean | string[];
+  run?: boolean | string[];
+  plugin?: boolean;
+  hrtime?: boolean;
+}
+
 /** Extended Deno Worker initialization options.
  * `noDenoNamespace` hides global `window.Deno` namespace for
  * spawned worker and nested workers spawned by it (default: false).
+ * `permissions` gives the worker its own permissions instead of sharing
+ * those of the parent.
  */
 export interface DenoWorkerOptions extends WorkerOptions {
   noDenoNamespace?: boolean;
+  permissions?: WorkerPermissions;
 }
 
 export class WorkerImpl implements Worker {
@@ -156,7 +176,8 @@ export class WorkerImpl implements Worker {
       specifier,
       includeDenoNamespace,
       hasSourceCode,
-      sourceCode
+      sourceCode,
+      options && options.permissions
     );
     this.run();
     this.isClosedPromise = hostGetWorkerClosed(this.id);
@@ -17,6 +17,8 @@ extern crate serde_derive;
 extern crate tokio;
 extern crate url;
 
+mod auth_tokens;
+mod cache;
 mod checksum;
 pub mod colors;
 pub mod compilers;
@@ -40,6 +42,8 @@ pub mod msg;
 pub mod ops;
 pub mod permissions;
 mod progress;
+#[cfg(unix)]
+mod pty;
 mod repl;
 pub mod resolve_addr;
 mod shell;
@@ -49,10 +53,15 @@ mod startup_data;
 pub mod state;
 pub mod test_util;
 mod tokio_util;
+#[cfg(unix)]
+mod tty;
+mod vendor;
 pub mod version;
 pub mod worker;
 
 use crate::deno_error::js_check;
+use crate::deno_error::DenoError;
+use crate::deno_error::ErrorKind;
 use crate::deno_error::{print_err_and_exit, print_msg_and_exit};
 use crate::global_state::ThreadSafeGlobalState;
 use crate::ops::io::get_stdio;
@@ -62,12 +71,15 @@ use crate::worker::Worker;
 use deno::v8_set_flags;
 use deno::ErrBox;
 use deno::ModuleSpecifier;
+use flags::CacheSubcommand;
 use flags::DenoFlags;
 use flags::DenoSubcommand;
 use log::Level;
 use log::Metadata;
 use log::Record;
 use std::env;
+use std::path::PathBuf;
+use url::Url;
 
 static LOGGER: Logger = Logger;
 
@@ -114,6 +126,11 @@ fn create_worker_and_state(
     .map_err(deno_error::print_err_and_exit)
     .unwrap();
 
+  let worker = create_main_worker(global_state.clone());
+  (worker, global_state)
+}
+
+fn create_main_worker(global_state: ThreadSafeGlobalState) -> Worker {
   let (int, ext) = ThreadSafeState::create_channels();
   let state = ThreadSafeState::new(
     global_state.clone(),
@@ -134,14 +151,12 @@ fn create_worker_and_state(
     resource_table.add("stderr", Box::new(stderr));
   }
 
-  let worker = Worker::new(
+  Worker::new(
     "main".to_string(),
     startup_data::deno_isolate_init(),
     state,
     ext,
-  );
-
-  (worker, global_state)
+  )
 }
 
 fn types_command() {
@@ -294,6 +309,118 @@ fn fetch_command(flags: DenoFlags) {
   tokio_util::run(main_future);
 }
 
+fn cache_command(flags: DenoFlags) {
+  let cache_subcommand = flags.cache_subcommand.clone().unwrap();
+  if cache_subcommand == CacheSubcommand::Prune {
+    return cache_prune_command(flags);
+  }
+
+  let global_state = ThreadSafeGlobalState::new(flags, Progress::new())
+    .map_err(deno_error::print_err_and_exit)
+    .unwrap();
+  let result = match cache_subcommand {
+    CacheSubcommand::List => cache::list(&global_state),
+    CacheSubcommand::Verify => cache::verify(&global_state).map(|ok| {
+      if !ok {
+        std::process::exit(1);
+      }
+    }),
+    CacheSubcommand::Clear => cache::clear(&global_state),
+    CacheSubcommand::Prune => unreachable!(),
+  };
+  if let Err(err) = result {
+    print_err_and_exit(err);
+  }
+}
+
+fn cache_prune_command(flags: DenoFlags) {
+  let (mut worker, state) = create_worker_and_state(flags);
+
+  let main_module = state.main_module.as_ref().unwrap().clone();
+
+  // Setup runtime.
+  js_check(worker.execute("denoMain()"));
+  debug!("main_module {}", main_module);
+
+  let main_future = async move {
+    // Nothing is removed unless every module in the graph could be loaded.
+    let result = load_module_graph(&mut worker, &main_module).await;
+    let modules = result.map_err(print_err_and_exit).unwrap();
+    if let Err(err) = cache::prune(&state, &modules) {
+      print_err_and_exit(err);
+    }
+    Ok(())
+  };
+
+  tokio_util::run(main_future);
+}
+
+fn vendor_command(flags: DenoFlags) {
+  let out_dir = flags.vendor_output.clone().unwrap();
+  let (mut worker, state) = create_worker_and_state(flags);
+
+  let main_module = state.main_module.as_ref().unwrap().clone();
+
+  // Setup runtime.
+  js_check(worker.execute("denoMain()"));
+  debug!("main_module {}", main_module);
+
+  let main_future = async move {
+    let result = load_module_graph(&mut worker, &main_module).await;
+    let modules = result.map_err(print_err_and_exit).unwrap();
+    let result =
+      vendor::vendor(state.clone(), modules, PathBuf::from(out_dir)).await;
+    let import_map_path = result.map_err(print_err_and_exit).unwrap();
+    if state.flags.lock_write {
+      write_lockfile(&state);
+    }
+    println!(
+      "{} deno run --importmap={} {}",
+      colors::bold("Vendored, run with:".to_string()),
+      import_map_path.display(),
+      main_module
+    );
+    Ok(())
+  };
+
+  tokio_util::run(main_future);
+}
+
+/// Loads the module graph of `main_module` without running it and returns
+/// the URLs of all statically imported modules.
+async fn load_module_graph(
+  worker: &mut Worker,
+  main_module: &ModuleSpecifier,
+) -> Result<Vec<Url>, ErrBox> {
+  worker.execute_mod_async(main_module, None, true).await?;
+  let maybe_deps = worker
+    .state
+    .modules
+    .lock()
+    .unwrap()
+    .deps(main_module.as_str());
+  match maybe_deps {
+    Some(deps) => Ok(collect_deps(&deps)),
+    None => Err(
+      DenoError::new(
+        ErrorKind::Other,
+        format!("Cannot retrieve the dependency graph of {}", main_module),
+      )
+      .into(),
+    ),
+  }
+}
+
+fn collect_deps(deps: &deno::Deps) -> Vec<Url> {
+  let mut out: Vec<Url> = Url::parse(&deps.name).into_iter().collect();
+  if let Some(ref children) = deps.deps {
+    for child in children {
+      out.extend(collect_deps(child));
+    }
+  }
+  out
+}
+
 fn eval_command(flags: DenoFlags) {
   let ts_source = flags.argv[1].clone();
   let (mut worker, _state) = create_worker_and_state(flags);
@@ -359,6 +486,162 @@ fn run_repl(flags: DenoFlags) {
   tokio_util::run(main_future);
 }
 
+fn write_lockfile(state: &ThreadSafeGlobalState) {
+  if let Some(ref lockfile) = state.lockfile {
+    let g = lockfile.lock().unwrap();
+    if let Err(e) = g.write() {
+      print_err_and_exit(ErrBox::from(e));
+    }
+  } else {
+    eprintln!("--lock flag must be specified when using --lock-write");
+    std::process::exit(11);
+  }
+}
+
+/// Interval at which `--watch` checks the modules for changes.
+const WATCH_INTERVAL_MS: u64 = 250;
+
+/// Local files of the modules imported by `main_module`, itself included.
+fn local_module_paths(
+  worker: &Worker,
+  main_module: &ModuleSpecifier,
+) -> Vec<PathBuf> {
+  fn collect(deps: &deno::Deps, paths: &mut Vec<PathBuf>) {
+    if let Ok(url) = Url::parse(&deps.name) {
+      if url.scheme() == "file" {
+        if let Ok(path) = url.to_file_path() {
+          if !paths.contains(&path) {
+            paths.push(path);
+          }
+        }
+      }
+    }
+    if let Some(children) = &deps.deps {
+      for child in children {
+        collect(child, paths);
+      }
+    }
+  }
+
+  let mut paths = Vec::new();
+  let modules = worker.state.modules.lock().unwrap();
+  if let Some(deps) = modules.deps(&main_module.to_string()) {
+    collect(&deps, &mut paths);
+  }
+  paths
+}
+
+fn modified_time(path: &std::path::Path) -> Option<std::time::SystemTime> {
+  std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Resolves with the paths whose modification time differs from the recorded
+/// one. Modification times are polled so that this works the same on every
+/// platform.
+async fn wait_for_change(
+  files: Vec<(PathBuf, Option<std::time::SystemTime>)>,
+) -> Vec<PathBuf> {
+  use std::time::{Duration, Instant};
+  loop {
+    let deadline = Instant::now() + Duration::from_millis(WATCH_INTERVAL_MS);
+    let delay = tokio::timer::Delay::new(deadline);
+    let _ = futures::compat::Compat01As03::new(delay).await;
+    let changed: Vec<PathBuf> = files
+      .iter()
+      .filter(|(path, time)| modified_time(path) != *time)
+      .map(|(path, _)| path.clone())
+      .collect();
+    if !changed.is_empty() {
+      return changed;
+    }
+  }
+}
+
+/// Runs the main module in a new worker every time one of the local modules
+/// it loaded changes. Errors are printed instead of exiting.
+async fn run_script_watch(
+  global_state: ThreadSafeGlobalState,
+  main_module: ModuleSpecifier,
+) {
+  use futures::future::Either;
+  use futures::future::FutureExt;
+
+  let mut paths: Vec<PathBuf> =
+    main_module.as_url().to_file_path().into_iter().collect();
+  loop {
+    // Modification times are recorded before the modules are loaded, so that
+    // changes made while they load or run cause a restart too.
+    let before: Vec<_> = paths
+      .iter()
+      .map(|path| (path.clone(), modified_time(path)))
+      .collect();
+    let mut worker = create_main_worker(global_state.clone());
+    js_check(worker.execute("denoMain()"));
+    let result = match worker.execute_mod_async(&main_module, None, true).await
+    {
+      Ok(()) => {
+        // Keep watching the previous modules if the graph failed to load.
+        paths = local_module_paths(&worker, &main_module);
+        worker.execute_mod_async(&main_module, None, false).await
+      }
+      Err(err) => Err(err),
+    };
+    // Modules that were not watched before are recorded once loaded.
+    let files: Vec<_> = paths
+      .iter()
+      .map(|path| match before.iter().find(|(p, _)| p == path) {
+        Some((_, time)) => (path.clone(), *time),
+        None => (path.clone(), modified_time(path)),
+      })
+      .collect();
+    match result {
+      Ok(()) => {
+        js_check(worker.execute("window.dispatchEvent(new Event('load'))"))
+      }
+      Err(err) => eprintln!("{}", err.to_string()),
+    }
+
+    let change = wait_for_change(files).boxed();
+    let changed = match futures::future::select(worker.clone(), change).await {
+      Either::Left((result, change)) => {
+        match result {
+          Ok(()) => {
+            js_check(
+              worker.execute("window.dispatchEvent(new Event('unload'))"),
+            );
+          }
+          Err(err) => eprintln!("{}", err.to_string()),
+        }
+        eprintln!("{}", colors::bold("Watcher: Process finished.".to_string()));
+        change.await
+      }
+      Either::Right((changed, _)) => changed,
+    };
+
+    // Resources like listeners may be held by tasks of the old worker that
+    // are still running. Closing them ends those tasks.
+    {
+      let mut table = worker.state.lock_resource_table();
+      for (rid, _) in table.entries() {
+        table.close(rid);
+      }
+    }
+    for path in &changed {
+      if let Ok(url) = Url::from_file_path(path) {
+        let specifier = ModuleSpecifier::from(url.clone());
+        global_state
+          .file_fetcher
+          .evict_cached_source_file(&specifier);
+        global_state.ts_compiler.unmark_compiled(&url);
+      }
+    }
+    eprintln!(
+      "{}",
+      colors::bold("Watcher: File change detected, restarting.".to_string())
+    );
+  }
+}
+
 fn run_script(flags: DenoFlags) {
   let use_current_thread = flags.current_thread;
   let (mut worker, state) = create_worker_and_state(flags);
@@ -368,6 +651,22 @@ fn run_script(flags: DenoFlags) {
     print_msg_and_exit("Please provide a name to the main script to run.");
   }
   let main_module = maybe_main_module.unwrap().clone();
+
+  if state.flags.watch {
+    // Every run gets a fresh worker.
+    drop(worker);
+    let main_future = async move {
+      run_script_watch(state, main_module).await;
+      Ok(())
+    };
+    if use_current_thread {
+      tokio_util::run_on_current_thread(main_future);
+    } else {
+      tokio_util::run(main_future);
+    }
+    return;
+  }
+
   // Normal situation of executing a module.
 
   // Setup runtime.
@@ -382,15 +681,7 @@ fn run_script(flags: DenoFlags) {
       print_err_and_exit(err);
     }
     if state.flags.lock_write {
-      if let Some(ref lockfile) = state.lockfile {
-        let g = lockfile.lock().unwrap();
-        if let Err(e) = g.write() {
-          print_err_and_exit(ErrBox::from(e));
-        }
-      } else {
-        eprintln!("--lock flag must be specified when using --lock-write");
-        std::process::exit(11);
-      }
+      write_lockfile(&state);
     }
     js_check(worker.execute("window.dispatchEvent(new Event('load'))"));
     let result = worker.await;
@@ -428,6 +719,7 @@ pub fn main() {
 
   match flags.subcommand {
     DenoSubcommand::Bundle => bundle_command(flags),
+    DenoSubcommand::Cache => cache_command(flags),
     DenoSubcommand::Completions => {}
     DenoSubcommand::Eval => eval_command(flags),
     DenoSubcommand::Fetch => fetch_command(flags),
@@ -435,6 +727,7 @@ pub fn main() {
     DenoSubcommand::Repl => run_repl(flags),
     DenoSubcommand::Run => run_script(flags),
     DenoSubcommand::Types => types_command(),
+    DenoSubcommand::Vendor => vendor_command(flags),
     _ => panic!("bad subcommand"),
   }
 }
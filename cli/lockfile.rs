@@ -1,26 +1,107 @@
-use crate::compilers::CompiledModule;
+use crate::file_fetcher::SourceFile;
+use crate::msg;
 use serde_json::json;
 pub use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::io::Error;
+use std::io::ErrorKind;
 use std::io::Result;
+use std::path::Path;
+use std::path::PathBuf;
+use url::Url;
+
+/// Version of the format written by `--lock-write`.
+///
+/// Version 1 files are a flat map of module name to a checksum of the
+/// *compiled* code of remote modules. Version 2 files hash the original
+/// source, also cover local modules and record redirects.
+const LOCKFILE_VERSION: u64 = 2;
+
+static VERSION: &str = "version";
+static MODULES: &str = "modules";
+static CHECKSUM: &str = "checksum";
+static MEDIA_TYPE: &str = "mediaType";
+static REDIRECT: &str = "redirect";
+
+/// Lockfile record for a single module URL.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LockfileEntry {
+  /// The URL redirected to the given URL, which has its own entry.
+  Redirect(String),
+  /// SHA-256 of the fetched source and the media type it was loaded as.
+  Source {
+    checksum: String,
+    media_type: String,
+  },
+}
+
+impl LockfileEntry {
+  pub fn from_source_file(source_file: &SourceFile) -> Self {
+    LockfileEntry::Source {
+      checksum: crate::checksum::gen(vec![&source_file.source_code]),
+      media_type: msg::enum_name_media_type(source_file.media_type).to_string(),
+    }
+  }
+
+  fn to_json(&self) -> Value {
+    match self {
+      LockfileEntry::Redirect(redirect_to) => json!({ REDIRECT: redirect_to }),
+      LockfileEntry::Source {
+        checksum,
+        media_type,
+      } => json!({
+        CHECKSUM: checksum,
+        MEDIA_TYPE: media_type,
+      }),
+    }
+  }
+
+  fn from_json(value: &Value) -> Option<Self> {
+    if let Some(redirect_to) = value[REDIRECT].as_str() {
+      return Some(LockfileEntry::Redirect(redirect_to.to_string()));
+    }
+    let checksum = value[CHECKSUM].as_str()?;
+    let media_type = value[MEDIA_TYPE].as_str()?;
+    Some(LockfileEntry::Source {
+      checksum: checksum.to_string(),
+      media_type: media_type.to_string(),
+    })
+  }
+}
 
 pub struct Lockfile {
   need_read: bool,
-  map: HashMap<String, String>,
+  map: BTreeMap<String, LockfileEntry>,
   pub filename: String,
+  /// Local modules are keyed relative to the directory of the lockfile, so
+  /// that the file can be committed alongside the code it covers.
+  base_dir: Option<PathBuf>,
 }
 
 impl Lockfile {
   pub fn new(filename: String) -> Lockfile {
+    let base_dir = std::env::current_dir()
+      .ok()
+      .and_then(|cwd| cwd.join(&filename).parent().map(Path::to_path_buf))
+      .map(|dir| dir.canonicalize().unwrap_or(dir));
     Lockfile {
-      map: HashMap::new(),
+      map: BTreeMap::new(),
       filename,
       need_read: true,
+      base_dir,
     }
   }
 
   pub fn write(&self) -> Result<()> {
-    let j = json!(self.map);
+    let modules: serde_json::Map<String, Value> = self
+      .map
+      .iter()
+      .map(|(key, entry)| (key.clone(), entry.to_json()))
+      .collect();
+    let j = json!({
+      VERSION: LOCKFILE_VERSION,
+      MODULES: modules,
+    });
     let s = serde_json::to_string_pretty(&j).unwrap();
     let mut f = std::fs::OpenOptions::new()
       .write(true)
@@ -33,38 +114,177 @@ impl Lockfile {
     Ok(())
   }
 
+  /// Reads a version 2 lockfile. Older formats are rejected since their
+  /// checksums cannot be verified against the source.
   pub fn read(&mut self) -> Result<()> {
     debug!("lockfile read {}", self.filename);
     let s = std::fs::read_to_string(&self.filename)?;
-    self.map = serde_json::from_str(&s)?;
+    let j: Value = serde_json::from_str(&s)?;
     self.need_read = false;
+    if j.get(VERSION).is_none() {
+      return Err(Error::new(
+        ErrorKind::InvalidData,
+        format!(
+          "Lockfile {} is outdated, regenerate it with --lock-write",
+          self.filename
+        ),
+      ));
+    }
+    self.map = parse_modules(&j).ok_or_else(|| {
+      Error::new(
+        ErrorKind::InvalidData,
+        format!("Lockfile {} is malformed", self.filename),
+      )
+    })?;
     Ok(())
   }
 
-  /// Lazily reads the filename, checks the given module is included.
-  /// Returns Ok(true) if check passed
-  pub fn check(&mut self, m: &CompiledModule) -> Result<bool> {
-    if m.name.starts_with("file:") {
-      return Ok(true);
+  /// Loads the existing lockfile so that `--lock-write` merges with, rather
+  /// than replaces, entries for modules not loaded by this run. Files in an
+  /// older format are migrated by dropping their entries.
+  fn read_for_write(&mut self) {
+    self.need_read = false;
+    let s = match std::fs::read_to_string(&self.filename) {
+      Ok(s) => s,
+      Err(_) => return,
+    };
+    let j: Value = match serde_json::from_str(&s) {
+      Ok(j) => j,
+      Err(_) => return,
+    };
+    if j.get(VERSION).is_none() {
+      eprintln!(
+        "Migrating lockfile {} to version {}",
+        self.filename, LOCKFILE_VERSION
+      );
+      return;
+    }
+    if let Some(map) = parse_modules(&j) {
+      self.map = map;
+    }
+  }
+
+  /// Returns the key under which the module is recorded.
+  fn key(&self, url: &Url) -> String {
+    if url.scheme() == "file" {
+      if let (Some(base_dir), Ok(path)) = (&self.base_dir, url.to_file_path()) {
+        if let Ok(relative) = path.strip_prefix(base_dir) {
+          let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+          return format!("./{}", components.join("/"));
+        }
+      }
     }
+    url.to_string()
+  }
+
+  /// Lazily reads the filename, checks the given module entry matches.
+  /// Returns Ok(true) if check passed
+  pub fn check(&mut self, url: &Url, entry: &LockfileEntry) -> Result<bool> {
     if self.need_read {
       self.read()?;
     }
     assert!(!self.need_read);
-    Ok(if let Some(lockfile_checksum) = self.map.get(&m.name) {
-      let compiled_checksum = crate::checksum::gen2(&m.code);
-      lockfile_checksum == &compiled_checksum
-    } else {
-      false
+    Ok(match self.map.get(&self.key(url)) {
+      Some(lockfile_entry) => lockfile_entry == entry,
+      None => false,
     })
   }
 
   // Returns true if module was not already inserted.
-  pub fn insert(&mut self, m: &CompiledModule) -> bool {
-    if m.name.starts_with("file:") {
-      return false;
+  pub fn insert(&mut self, url: &Url, entry: LockfileEntry) -> bool {
+    if self.need_read {
+      self.read_for_write();
+    }
+    let key = self.key(url);
+    self.map.insert(key, entry).is_none()
+  }
+}
+
+fn parse_modules(j: &Value) -> Option<BTreeMap<String, LockfileEntry>> {
+  let modules = j[MODULES].as_object()?;
+  let mut map = BTreeMap::new();
+  for (key, value) in modules.iter() {
+    map.insert(key.clone(), LockfileEntry::from_json(value)?);
+  }
+  Some(map)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  fn source_entry(checksum: &str) -> LockfileEntry {
+    LockfileEntry::Source {
+      checksum: checksum.to_string(),
+      media_type: "TypeScript".to_string(),
     }
-    let checksum = crate::checksum::gen2(&m.code);
-    self.map.insert(m.name.clone(), checksum).is_none()
+  }
+
+  #[test]
+  fn test_write_and_check() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let filename = temp_dir.path().join("lock.json");
+    let filename = filename.to_str().unwrap().to_string();
+    let remote = Url::parse("https://deno.land/x/mod.ts").unwrap();
+    let redirect = Url::parse("https://deno.land/x/latest/mod.ts").unwrap();
+    let local_path = temp_dir.path().canonicalize().unwrap().join("main.ts");
+    let local = Url::from_file_path(local_path).unwrap();
+
+    let mut lockfile = Lockfile::new(filename.clone());
+    assert!(lockfile.insert(&remote, source_entry("abc")));
+    assert!(
+      lockfile.insert(&redirect, LockfileEntry::Redirect(remote.to_string()))
+    );
+    assert!(lockfile.insert(&local, source_entry("def")));
+    assert!(!lockfile.insert(&local, source_entry("def")));
+    lockfile.write().unwrap();
+
+    let j: Value =
+      serde_json::from_str(&std::fs::read_to_string(&filename).unwrap())
+        .unwrap();
+    assert_eq!(j[VERSION], json!(2));
+    assert_eq!(j[MODULES]["./main.ts"][CHECKSUM], json!("def"));
+    assert_eq!(
+      j[MODULES]["https://deno.land/x/latest/mod.ts"][REDIRECT],
+      json!("https://deno.land/x/mod.ts")
+    );
+
+    let mut lockfile = Lockfile::new(filename);
+    assert!(lockfile.check(&remote, &source_entry("abc")).unwrap());
+    assert!(!lockfile.check(&remote, &source_entry("bad")).unwrap());
+    assert!(lockfile
+      .check(&redirect, &LockfileEntry::Redirect(remote.to_string()))
+      .unwrap());
+    assert!(lockfile.check(&local, &source_entry("def")).unwrap());
+    let unknown = Url::parse("https://deno.land/x/other.ts").unwrap();
+    assert!(!lockfile.check(&unknown, &source_entry("abc")).unwrap());
+  }
+
+  #[test]
+  fn test_migrate_version_1() {
+    let temp_dir = TempDir::new().expect("tempdir fail");
+    let filename = temp_dir.path().join("lock.json");
+    std::fs::write(
+      &filename,
+      r#"{ "https://deno.land/x/mod.ts": "5c93c66125878389" }"#,
+    )
+    .unwrap();
+    let filename = filename.to_str().unwrap().to_string();
+    let remote = Url::parse("https://deno.land/x/mod.ts").unwrap();
+
+    let mut lockfile = Lockfile::new(filename.clone());
+    let err = lockfile.check(&remote, &source_entry("abc")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let mut lockfile = Lockfile::new(filename.clone());
+    assert!(lockfile.insert(&remote, source_entry("abc")));
+    lockfile.write().unwrap();
+
+    let mut lockfile = Lockfile::new(filename);
+    assert!(lockfile.check(&remote, &source_entry("abc")).unwrap());
   }
 }
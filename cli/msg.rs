@@ -61,6 +61,7 @@ pub enum ErrorKind {
 
   /** TODO this is a DomException type, and should be moved out of here when possible */
   DataCloneError = 52,
+  IntegrityCheckFailed = 53,
 }
 
 // Warning! The values in this enum are duplicated in js/compiler.ts
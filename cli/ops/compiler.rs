@@ -4,21 +4,14 @@ use crate::futures::future::try_join_all;
 use crate::futures::future::FutureExt;
 use crate::futures::future::TryFutureExt;
 use crate::msg;
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::Loader;
 use deno::*;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("cache", s.core_op(json_op(s.stateful_op(op_cache))));
-  i.register_op(
-    "fetch_source_files",
-    s.core_op(json_op(s.stateful_op(op_fetch_source_files))),
-  );
-  i.register_op(
-    "fetch_asset",
-    s.core_op(json_op(s.stateful_op(op_fetch_asset))),
-  );
+  s.register_json_op(i, "cache", op_cache);
+  s.register_json_op(i, "fetch_source_files", op_fetch_source_files);
+  s.register_json_op(i, "fetch_asset", op_fetch_asset);
 }
 
 #[derive(Deserialize)]
@@ -1,7 +1,6 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{Deserialize, JsonOp, Value};
 use crate::fmt_errors::JSError;
-use crate::ops::json_op;
 use crate::source_maps::get_orig_position;
 use crate::source_maps::CachedMaps;
 use crate::state::ThreadSafeState;
@@ -9,14 +8,8 @@ use deno::*;
 use std::collections::HashMap;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op(
-    "apply_source_map",
-    s.core_op(json_op(s.stateful_op(op_apply_source_map))),
-  );
-  i.register_op(
-    "format_error",
-    s.core_op(json_op(s.stateful_op(op_format_error))),
-  );
+  s.register_json_op(i, "apply_source_map", op_apply_source_map);
+  s.register_json_op(i, "format_error", op_format_error);
 }
 
 #[derive(Deserialize)]
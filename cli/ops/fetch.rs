@@ -1,28 +1,66 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{Deserialize, JsonOp, Value};
-use super::io::StreamResource;
+use super::io::{ResourceBody, StreamResource};
+use crate::deno_error::bad_resource;
+use crate::deno_error::DenoError;
+use crate::deno_error::ErrorKind;
 use crate::http_body::HttpBody;
-use crate::http_util::get_client;
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::*;
+use futures::future::AbortHandle;
+use futures::future::AbortRegistration;
+use futures::future::Abortable;
 use futures::future::FutureExt;
 use futures::future::TryFutureExt;
+use futures::stream::TryStreamExt;
 use http::header::HeaderName;
 use http::header::HeaderValue;
 use http::Method;
+use reqwest::r#async::Body;
 use std;
 use std::convert::From;
+use std::time::Duration;
+use tokio::timer::timeout;
+use tokio::timer::Timeout;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("fetch", s.core_op(json_op(s.stateful_op(op_fetch))));
+  s.register_json_op(i, "fetch", op_fetch);
+  s.register_json_op(i, "fetch_cancel_handle", op_fetch_cancel_handle);
+  s.register_json_op(i, "fetch_cancel", op_fetch_cancel);
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct FetchArgs {
   method: Option<String>,
   url: String,
   headers: Vec<(String, String)>,
+  /// Resource to stream the request body from, instead of the zero copy
+  /// buffer.
+  body_rid: Option<u32>,
+  /// Milliseconds to wait for the response headers.
+  timeout: Option<u64>,
+  /// Handle created by `op_fetch_cancel_handle`.
+  cancel_rid: Option<u32>,
+}
+
+/// Allows an in-flight fetch to be aborted. The registration is taken by the
+/// fetch it is passed to, the handle stays in the table until it is closed.
+struct FetchCancelHandle {
+  handle: AbortHandle,
+  registration: Option<AbortRegistration>,
+}
+
+impl Resource for FetchCancelHandle {}
+
+fn timeout_error(err: timeout::Error<reqwest::Error>) -> ErrBox {
+  if err.is_elapsed() {
+    DenoError::new(ErrorKind::TimedOut, "Request timed out".to_string()).into()
+  } else if err.is_inner() {
+    ErrBox::from(err.into_inner().unwrap())
+  } else {
+    DenoError::new(ErrorKind::Other, err.to_string()).into()
+  }
 }
 
 pub fn op_fetch(
@@ -33,7 +71,7 @@ pub fn op_fetch(
   let args: FetchArgs = serde_json::from_value(args)?;
   let url = args.url;
 
-  let client = get_client();
+  let client = state.global_state.http_client.clone();
 
   let method = match args.method {
     Some(method_str) => Method::from_bytes(method_str.as_bytes())?,
@@ -43,9 +81,37 @@ pub fn op_fetch(
   let url_ = url::Url::parse(&url).map_err(ErrBox::from)?;
   state.check_net_url(&url_)?;
 
+  if let Some(body_rid) = args.body_rid {
+    state
+      .lock_resource_table()
+      .get::<StreamResource>(body_rid)
+      .ok_or_else(bad_resource)?;
+  }
+
+  let registration = match args.cancel_rid {
+    Some(cancel_rid) => {
+      let mut table = state.lock_resource_table();
+      let cancel_handle = table
+        .get_mut::<FetchCancelHandle>(cancel_rid)
+        .ok_or_else(bad_resource)?;
+      let registration =
+        cancel_handle.registration.take().ok_or_else(|| {
+          DenoError::new(
+            ErrorKind::Other,
+            "Cancel handle is already in use".to_string(),
+          )
+        })?;
+      Some(registration)
+    }
+    None => None,
+  };
+
   let mut request = client.request(method, url_);
 
-  if let Some(buf) = data {
+  if let Some(body_rid) = args.body_rid {
+    let body = ResourceBody::new(state, body_rid);
+    request = request.body(Body::wrap_stream(body.compat()));
+  } else if let Some(buf) = data {
     request = request.body(Vec::from(&*buf));
   }
 
@@ -55,33 +121,95 @@ pub fn op_fetch(
     request = request.header(name, v);
   }
   debug!("Before fetch {}", url);
+  let send = match args.timeout {
+    Some(ms) => {
+      let send = Timeout::new(request.send(), Duration::from_millis(ms));
+      futures::compat::Compat01As03::new(send)
+        .map_err(timeout_error)
+        .boxed()
+    }
+    None => futures::compat::Compat01As03::new(request.send())
+      .map_err(ErrBox::from)
+      .boxed(),
+  };
+  let send = match registration {
+    Some(registration) => Abortable::new(send, registration)
+      .map(|result| match result {
+        Ok(result) => result,
+        Err(_) => Err(
+          DenoError::new(
+            ErrorKind::HttpCanceled,
+            "Request has been canceled".to_string(),
+          )
+          .into(),
+        ),
+      })
+      .boxed(),
+    None => send,
+  };
   let state_ = state.clone();
-  let future = futures::compat::Compat01As03::new(request.send())
-    .map_err(ErrBox::from)
-    .and_then(move |res| {
-      debug!("Fetch response {}", url);
-      let status = res.status();
-      let mut res_headers = Vec::new();
-      for (key, val) in res.headers().iter() {
-        res_headers.push((key.to_string(), val.to_str().unwrap().to_owned()));
-      }
-
-      let body = HttpBody::from(res.into_body());
-      let mut table = state_.lock_resource_table();
-      let rid = table.add(
-        "httpBody",
-        Box::new(StreamResource::HttpBody(Box::new(body))),
-      );
-
-      let json_res = json!({
-        "bodyRid": rid,
-        "status": status.as_u16(),
-        "statusText": status.canonical_reason().unwrap_or(""),
-        "headers": res_headers
-      });
-
-      futures::future::ok(json_res)
+  let future = send.and_then(move |res| {
+    debug!("Fetch response {}", url);
+    let status = res.status();
+    let mut res_headers = Vec::new();
+    for (key, val) in res.headers().iter() {
+      res_headers.push((key.to_string(), val.to_str().unwrap().to_owned()));
+    }
+
+    let body = HttpBody::from(res.into_body());
+    let mut table = state_.lock_resource_table();
+    let rid = table.add(
+      "httpBody",
+      Box::new(StreamResource::HttpBody(Box::new(body))),
+    );
+
+    let json_res = json!({
+      "bodyRid": rid,
+      "status": status.as_u16(),
+      "statusText": status.canonical_reason().unwrap_or(""),
+      "headers": res_headers
     });
 
+    futures::future::ok(json_res)
+  });
+
   Ok(JsonOp::Async(future.boxed()))
 }
+
+pub fn op_fetch_cancel_handle(
+  state: &ThreadSafeState,
+  _args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let (handle, registration) = AbortHandle::new_pair();
+  let mut table = state.lock_resource_table();
+  let rid = table.add(
+    "fetchCancelHandle",
+    Box::new(FetchCancelHandle {
+      handle,
+      registration: Some(registration),
+    }),
+  );
+  Ok(JsonOp::Sync(json!(rid)))
+}
+
+#[derive(Deserialize)]
+struct FetchCancelArgs {
+  rid: i32,
+}
+
+/// Aborts the fetch using the handle. The pending request is dropped and
+/// its op rejects with `HttpCanceled`.
+pub fn op_fetch_cancel(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FetchCancelArgs = serde_json::from_value(args)?;
+  let table = state.lock_resource_table();
+  let cancel_handle = table
+    .get::<FetchCancelHandle>(args.rid as u32)
+    .ok_or_else(bad_resource)?;
+  cancel_handle.handle.abort();
+  Ok(JsonOp::Sync(json!({})))
+}
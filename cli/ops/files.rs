@@ -1,11 +1,11 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
-use super::dispatch_json::{Deserialize, JsonOp, Value};
+use super::dispatch_json::{blocking_json, Deserialize, JsonOp, Value};
+use super::fs::get_stat_json;
 use super::io::StreamResource;
 use crate::deno_error::bad_resource;
 use crate::deno_error::DenoError;
 use crate::deno_error::ErrorKind;
 use crate::fs as deno_fs;
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::*;
 use futures::future::FutureExt;
@@ -20,9 +20,15 @@ use std::task::Poll;
 use tokio;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("open", s.core_op(json_op(s.stateful_op(op_open))));
-  i.register_op("close", s.core_op(json_op(s.stateful_op(op_close))));
-  i.register_op("seek", s.core_op(json_op(s.stateful_op(op_seek))));
+  s.register_json_op(i, "open", op_open);
+  s.register_json_op(i, "close", op_close);
+  s.register_json_op(i, "seek", op_seek);
+  s.register_json_op(i, "flock", op_flock);
+  s.register_json_op(i, "funlock", op_funlock);
+  s.register_json_op(i, "fsync", op_fsync);
+  s.register_json_op(i, "fdatasync", op_fdatasync);
+  s.register_json_op(i, "ftruncate", op_ftruncate);
+  s.register_json_op(i, "fstat", op_fstat);
 }
 
 #[derive(Deserialize)]
@@ -30,66 +36,122 @@ pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
 struct OpenArgs {
   promise_id: Option<u64>,
   filename: String,
-  mode: String,
+  /// fopen-style mode like "r" or "w+". Either this or `options` is given.
+  mode: Option<String>,
+  options: Option<OpenOptions>,
 }
 
-fn op_open(
-  state: &ThreadSafeState,
-  args: Value,
-  _zero_copy: Option<PinnedBuf>,
-) -> Result<JsonOp, ErrBox> {
-  let args: OpenArgs = serde_json::from_value(args)?;
-  let (filename, filename_) = deno_fs::resolve_from_cwd(&args.filename)?;
-  let mode = args.mode.as_ref();
-  let state_ = state.clone();
-  let mut open_options = tokio::fs::OpenOptions::new();
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct OpenOptions {
+  read: bool,
+  write: bool,
+  append: bool,
+  truncate: bool,
+  create: bool,
+  create_new: bool,
+  /// Permission bits of a created file, before the umask. Unix only.
+  mode: Option<u32>,
+}
 
+/// Translates an fopen-style mode to open options.
+fn open_options_from_mode(mode: &str) -> Result<OpenOptions, ErrBox> {
+  let mut options = OpenOptions::default();
   match mode {
     "r" => {
-      open_options.read(true);
+      options.read = true;
     }
     "r+" => {
-      open_options.read(true).write(true);
+      options.read = true;
+      options.write = true;
     }
     "w" => {
-      open_options.create(true).write(true).truncate(true);
+      options.create = true;
+      options.write = true;
+      options.truncate = true;
     }
     "w+" => {
-      open_options
-        .read(true)
-        .create(true)
-        .write(true)
-        .truncate(true);
+      options.read = true;
+      options.create = true;
+      options.write = true;
+      options.truncate = true;
     }
     "a" => {
-      open_options.create(true).append(true);
+      options.create = true;
+      options.append = true;
     }
     "a+" => {
-      open_options.read(true).create(true).append(true);
+      options.read = true;
+      options.create = true;
+      options.append = true;
     }
     "x" => {
-      open_options.create_new(true).write(true);
+      options.create_new = true;
+      options.write = true;
     }
     "x+" => {
-      open_options.create_new(true).read(true).write(true);
+      options.create_new = true;
+      options.read = true;
+      options.write = true;
     }
     &_ => {
-      panic!("Unknown file open mode.");
+      return Err(
+        DenoError::new(
+          ErrorKind::InvalidInput,
+          format!("Unknown file open mode: {}", mode),
+        )
+        .into(),
+      );
     }
   }
+  Ok(options)
+}
 
-  match mode {
-    "r" => {
-      state.check_read(&filename_)?;
-    }
-    "w" | "a" | "x" => {
-      state.check_write(&filename_)?;
-    }
-    &_ => {
-      state.check_read(&filename_)?;
-      state.check_write(&filename_)?;
+fn op_open(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: OpenArgs = serde_json::from_value(args)?;
+  let (filename, filename_) = deno_fs::resolve_from_cwd(&args.filename)?;
+  let options = match (args.options, args.mode) {
+    (Some(options), _) => options,
+    (None, Some(mode)) => open_options_from_mode(&mode)?,
+    (None, None) => open_options_from_mode("r")?,
+  };
+  let state_ = state.clone();
+
+  if options.read {
+    state.check_read(&filename_)?;
+  }
+  // Anything that can create or change the file needs write access.
+  if options.write
+    || options.append
+    || options.truncate
+    || options.create
+    || options.create_new
+  {
+    state.check_write(&filename_)?;
+  }
+
+  let mut std_options = std::fs::OpenOptions::new();
+  std_options
+    .read(options.read)
+    .write(options.write)
+    .append(options.append)
+    .truncate(options.truncate)
+    .create(options.create)
+    .create_new(options.create_new);
+  if let Some(mode) = options.mode {
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::OpenOptionsExt;
+      std_options.mode(mode);
     }
+    #[cfg(not(unix))]
+    let _ = mode;
   }
+  let open_options = tokio::fs::OpenOptions::from(std_options);
 
   let is_sync = args.promise_id.is_none();
   let op = futures::compat::Compat01As03::new(tokio::prelude::Future::map_err(
@@ -203,3 +265,240 @@ fn op_seek(
     Ok(JsonOp::Async(op.boxed()))
   }
 }
+
+/// Polls `f` with the file of a `StreamResource::FsFile` until it is ready.
+fn poll_fs_file<T, F>(
+  state: &ThreadSafeState,
+  rid: ResourceId,
+  mut f: F,
+) -> impl Future<Output = Result<T, ErrBox>>
+where
+  F: FnMut(&mut tokio::fs::File) -> tokio::prelude::Poll<T, std::io::Error>,
+{
+  let state = state.clone();
+  futures::future::poll_fn(move |_cx| {
+    let mut table = state.lock_resource_table();
+    let tokio_file = match table.get_mut::<StreamResource>(rid) {
+      Some(StreamResource::FsFile(ref mut file)) => file,
+      _ => return Poll::Ready(Err(bad_resource())),
+    };
+
+    use tokio::prelude::Async::*;
+
+    match f(tokio_file) {
+      Ok(Ready(v)) => Poll::Ready(Ok(v)),
+      Err(err) => Poll::Ready(Err(ErrBox::from(err))),
+      Ok(NotReady) => Poll::Pending,
+    }
+  })
+}
+
+/// Duplicates the descriptor of a file resource. The duplicate shares the
+/// open file description, and with it the offset and `flock` locks.
+fn clone_std_file(
+  state: &ThreadSafeState,
+  rid: ResourceId,
+) -> Result<std::fs::File, ErrBox> {
+  let fut = poll_fs_file(state, rid, tokio::fs::File::poll_try_clone);
+  let tokio_file = futures::executor::block_on(fut)?;
+  Ok(tokio_file.into_std())
+}
+
+#[cfg(unix)]
+fn flock(
+  file: &std::fs::File,
+  arg: nix::fcntl::FlockArg,
+) -> Result<(), ErrBox> {
+  use std::os::unix::io::AsRawFd;
+  nix::fcntl::flock(file.as_raw_fd(), arg)?;
+  Ok(())
+}
+
+#[cfg(not(unix))]
+fn locking_not_supported() -> ErrBox {
+  DenoError::new(
+    ErrorKind::Other,
+    "Locking files is only supported on Unix".to_string(),
+  )
+  .into()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FlockArgs {
+  promise_id: Option<u64>,
+  rid: i32,
+  exclusive: bool,
+}
+
+/// Takes an advisory lock on the file, waiting until other holders release
+/// it. The lock is released by `op_funlock` or by closing the file.
+fn op_flock(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FlockArgs = serde_json::from_value(args)?;
+  let file = clone_std_file(state, args.rid as u32)?;
+  let exclusive = args.exclusive;
+
+  let is_sync = args.promise_id.is_none();
+  blocking_json(is_sync, move || {
+    #[cfg(unix)]
+    {
+      use nix::fcntl::FlockArg;
+      let arg = if exclusive {
+        FlockArg::LockExclusive
+      } else {
+        FlockArg::LockShared
+      };
+      flock(&file, arg)?;
+      Ok(json!({}))
+    }
+    #[cfg(not(unix))]
+    {
+      let _ = (file, exclusive);
+      Err(locking_not_supported())
+    }
+  })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FunlockArgs {
+  promise_id: Option<u64>,
+  rid: i32,
+}
+
+fn op_funlock(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FunlockArgs = serde_json::from_value(args)?;
+  let file = clone_std_file(state, args.rid as u32)?;
+
+  let is_sync = args.promise_id.is_none();
+  blocking_json(is_sync, move || {
+    #[cfg(unix)]
+    {
+      flock(&file, nix::fcntl::FlockArg::Unlock)?;
+      Ok(json!({}))
+    }
+    #[cfg(not(unix))]
+    {
+      let _ = file;
+      Err(locking_not_supported())
+    }
+  })
+}
+
+/// Runs an operation on a file resource, blocking when it is synchronous.
+fn fs_file_op<T, F, R>(
+  state: &ThreadSafeState,
+  promise_id: Option<u64>,
+  rid: i32,
+  f: F,
+  result: R,
+) -> Result<JsonOp, ErrBox>
+where
+  T: 'static,
+  F: FnMut(&mut tokio::fs::File) -> tokio::prelude::Poll<T, std::io::Error>
+    + Send
+    + 'static,
+  R: FnOnce(T) -> Value + Send + 'static,
+{
+  let op = poll_fs_file(state, rid as u32, f).map_ok(result);
+  if promise_id.is_none() {
+    let buf = futures::executor::block_on(op)?;
+    Ok(JsonOp::Sync(buf))
+  } else {
+    Ok(JsonOp::Async(op.boxed()))
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FsyncArgs {
+  promise_id: Option<u64>,
+  rid: i32,
+}
+
+/// Flushes the data and metadata of the file to disk.
+fn op_fsync(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FsyncArgs = serde_json::from_value(args)?;
+  fs_file_op(
+    state,
+    args.promise_id,
+    args.rid,
+    tokio::fs::File::poll_sync_all,
+    |_| json!({}),
+  )
+}
+
+/// Flushes the data of the file to disk, and only the metadata needed to
+/// read it back.
+fn op_fdatasync(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FsyncArgs = serde_json::from_value(args)?;
+  fs_file_op(
+    state,
+    args.promise_id,
+    args.rid,
+    tokio::fs::File::poll_sync_data,
+    |_| json!({}),
+  )
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FtruncateArgs {
+  promise_id: Option<u64>,
+  rid: i32,
+  len: u64,
+}
+
+fn op_ftruncate(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FtruncateArgs = serde_json::from_value(args)?;
+  let len = args.len;
+  fs_file_op(
+    state,
+    args.promise_id,
+    args.rid,
+    move |file| file.poll_set_len(len),
+    |_| json!({}),
+  )
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FstatArgs {
+  promise_id: Option<u64>,
+  rid: i32,
+}
+
+fn op_fstat(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FstatArgs = serde_json::from_value(args)?;
+  fs_file_op(
+    state,
+    args.promise_id,
+    args.rid,
+    tokio::fs::File::poll_metadata,
+    |metadata| get_stat_json(&metadata),
+  )
+}
@@ -4,7 +4,6 @@ use super::dispatch_json::{blocking_json, Deserialize, JsonOp, Value};
 use crate::deno_error::DenoError;
 use crate::deno_error::ErrorKind;
 use crate::fs as deno_fs;
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::*;
 use remove_dir_all::remove_dir_all;
@@ -17,26 +16,23 @@ use std::time::UNIX_EPOCH;
 use std::os::unix::fs::PermissionsExt;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("chdir", s.core_op(json_op(s.stateful_op(op_chdir))));
-  i.register_op("mkdir", s.core_op(json_op(s.stateful_op(op_mkdir))));
-  i.register_op("chmod", s.core_op(json_op(s.stateful_op(op_chmod))));
-  i.register_op("chown", s.core_op(json_op(s.stateful_op(op_chown))));
-  i.register_op("remove", s.core_op(json_op(s.stateful_op(op_remove))));
-  i.register_op("copy_file", s.core_op(json_op(s.stateful_op(op_copy_file))));
-  i.register_op("stat", s.core_op(json_op(s.stateful_op(op_stat))));
-  i.register_op("realpath", s.core_op(json_op(s.stateful_op(op_realpath))));
-  i.register_op("read_dir", s.core_op(json_op(s.stateful_op(op_read_dir))));
-  i.register_op("rename", s.core_op(json_op(s.stateful_op(op_rename))));
-  i.register_op("link", s.core_op(json_op(s.stateful_op(op_link))));
-  i.register_op("symlink", s.core_op(json_op(s.stateful_op(op_symlink))));
-  i.register_op("read_link", s.core_op(json_op(s.stateful_op(op_read_link))));
-  i.register_op("truncate", s.core_op(json_op(s.stateful_op(op_truncate))));
-  i.register_op(
-    "make_temp_dir",
-    s.core_op(json_op(s.stateful_op(op_make_temp_dir))),
-  );
-  i.register_op("cwd", s.core_op(json_op(s.stateful_op(op_cwd))));
-  i.register_op("utime", s.core_op(json_op(s.stateful_op(op_utime))));
+  s.register_json_op(i, "chdir", op_chdir);
+  s.register_json_op(i, "mkdir", op_mkdir);
+  s.register_json_op(i, "chmod", op_chmod);
+  s.register_json_op(i, "chown", op_chown);
+  s.register_json_op(i, "remove", op_remove);
+  s.register_json_op(i, "copy_file", op_copy_file);
+  s.register_json_op(i, "stat", op_stat);
+  s.register_json_op(i, "realpath", op_realpath);
+  s.register_json_op(i, "read_dir", op_read_dir);
+  s.register_json_op(i, "rename", op_rename);
+  s.register_json_op(i, "link", op_link);
+  s.register_json_op(i, "symlink", op_symlink);
+  s.register_json_op(i, "read_link", op_read_link);
+  s.register_json_op(i, "truncate", op_truncate);
+  s.register_json_op(i, "make_temp_dir", op_make_temp_dir);
+  s.register_json_op(i, "cwd", op_cwd);
+  s.register_json_op(i, "utime", op_utime);
 }
 
 #[derive(Deserialize)]
@@ -265,16 +261,21 @@ fn op_stat(
       fs::metadata(&filename)?
     };
 
-    Ok(json!({
-      "isFile": metadata.is_file(),
-      "isSymlink": metadata.file_type().is_symlink(),
-      "len": metadata.len(),
-      "modified":to_seconds!(metadata.modified()),
-      "accessed":to_seconds!(metadata.accessed()),
-      "created":to_seconds!(metadata.created()),
-      "mode": get_mode(&metadata.permissions()),
-      "hasMode": cfg!(target_family = "unix"), // false on windows,
-    }))
+    Ok(get_stat_json(&metadata))
+  })
+}
+
+/// Response of `op_stat`, also used by `op_fstat`.
+pub fn get_stat_json(metadata: &fs::Metadata) -> Value {
+  json!({
+    "isFile": metadata.is_file(),
+    "isSymlink": metadata.file_type().is_symlink(),
+    "len": metadata.len(),
+    "modified":to_seconds!(metadata.modified()),
+    "accessed":to_seconds!(metadata.accessed()),
+    "created":to_seconds!(metadata.created()),
+    "mode": get_mode(&metadata.permissions()),
+    "hasMode": cfg!(target_family = "unix"), // false on windows,
   })
 }
 
@@ -0,0 +1,389 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use super::dispatch_json::{Deserialize, JsonOp, Value};
+use crate::fs as deno_fs;
+use crate::state::ThreadSafeState;
+use deno::*;
+
+#[cfg(target_os = "linux")]
+use crate::deno_error::bad_resource;
+#[cfg(target_os = "linux")]
+use futures::channel::mpsc;
+#[cfg(target_os = "linux")]
+use futures::future::FutureExt;
+#[cfg(target_os = "linux")]
+use futures::stream::StreamExt;
+#[cfg(target_os = "linux")]
+use futures::task::AtomicWaker;
+#[cfg(target_os = "linux")]
+use nix::errno::Errno;
+#[cfg(target_os = "linux")]
+use nix::poll::{poll, PollFd, PollFlags};
+#[cfg(target_os = "linux")]
+use nix::sys::inotify::{
+  AddWatchFlags, InitFlags, Inotify, InotifyEvent, WatchDescriptor,
+};
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "linux")]
+use std::task::Poll;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
+
+#[cfg(not(target_os = "linux"))]
+use crate::deno_error::{DenoError, ErrorKind};
+
+pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
+  s.register_json_op(i, "fs_events_open", op_fs_events_open);
+  s.register_json_op(i, "fs_events_poll", op_fs_events_poll);
+}
+
+/// How long the watcher collects further changes after the first pending one,
+/// before the pending events are delivered as one batch.
+#[cfg(target_os = "linux")]
+const DEBOUNCE_MS: libc::c_int = 50;
+
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, PartialEq)]
+struct FsEvent {
+  kind: &'static str,
+  paths: Vec<PathBuf>,
+  /// Pairs the two halves of a rename.
+  cookie: u32,
+}
+
+#[cfg(target_os = "linux")]
+impl FsEvent {
+  fn to_json(&self) -> Value {
+    let paths: Vec<String> = self
+      .paths
+      .iter()
+      .map(|path| path.to_string_lossy().into_owned())
+      .collect();
+    json!({
+      "kind": self.kind,
+      "paths": paths,
+    })
+  }
+}
+
+/// Batches of changes, sent by the thread reading from inotify. Dropping it
+/// stops that thread and lets a pending `op_fs_events_poll` resolve.
+#[cfg(target_os = "linux")]
+struct FsEventsResource {
+  receiver: mpsc::UnboundedReceiver<Vec<FsEvent>>,
+  /// Write end of a pipe, closed to stop the thread.
+  shutdown: RawFd,
+  waker: AtomicWaker,
+}
+
+#[cfg(target_os = "linux")]
+impl Resource for FsEventsResource {}
+
+#[cfg(target_os = "linux")]
+impl Drop for FsEventsResource {
+  fn drop(&mut self) {
+    let _ = nix::unistd::close(self.shutdown);
+    self.waker.wake();
+  }
+}
+
+#[cfg(target_os = "linux")]
+struct Watcher {
+  inotify: Inotify,
+  recursive: bool,
+  paths: HashMap<WatchDescriptor, PathBuf>,
+}
+
+#[cfg(target_os = "linux")]
+impl Watcher {
+  fn new(recursive: bool) -> Result<Self, ErrBox> {
+    let inotify =
+      Inotify::init(InitFlags::IN_CLOEXEC | InitFlags::IN_NONBLOCK)?;
+    Ok(Self {
+      inotify,
+      recursive,
+      paths: HashMap::new(),
+    })
+  }
+
+  /// Watches the path, and all directories below it when recursive.
+  fn add(&mut self, path: &Path) -> Result<(), ErrBox> {
+    let mask = AddWatchFlags::IN_CREATE
+      | AddWatchFlags::IN_MODIFY
+      | AddWatchFlags::IN_ATTRIB
+      | AddWatchFlags::IN_DELETE
+      | AddWatchFlags::IN_DELETE_SELF
+      | AddWatchFlags::IN_MOVED_FROM
+      | AddWatchFlags::IN_MOVED_TO
+      | AddWatchFlags::IN_MOVE_SELF;
+    let wd = self.inotify.add_watch(path, mask)?;
+    self.paths.insert(wd, path.to_owned());
+    if self.recursive && path.is_dir() {
+      for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+          self.add(&entry.path())?;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Adds the event to the pending batch, unless an equal one is pending
+  /// already.
+  fn handle(&mut self, event: InotifyEvent, pending: &mut Vec<FsEvent>) {
+    if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+      self.paths.remove(&event.wd);
+      return;
+    }
+    if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+      debug!("inotify queue overflowed, events were lost");
+      return;
+    }
+    let path = match (self.paths.get(&event.wd), &event.name) {
+      (Some(base), Some(name)) => base.join(name),
+      (Some(base), None) => base.clone(),
+      (None, _) => return,
+    };
+
+    let kind = if event.mask.contains(AddWatchFlags::IN_CREATE) {
+      // Directories created below a recursive watch are watched as well.
+      if self.recursive && event.mask.contains(AddWatchFlags::IN_ISDIR) {
+        if let Err(err) = self.add(&path) {
+          debug!("Failed to watch {}: {}", path.display(), err);
+        }
+      }
+      "create"
+    } else if event
+      .mask
+      .intersects(AddWatchFlags::IN_DELETE | AddWatchFlags::IN_DELETE_SELF)
+    {
+      "remove"
+    } else if event.mask.intersects(
+      AddWatchFlags::IN_MOVED_FROM
+        | AddWatchFlags::IN_MOVED_TO
+        | AddWatchFlags::IN_MOVE_SELF,
+    ) {
+      "rename"
+    } else {
+      "modify"
+    };
+
+    // The destination of a rename joins the event of its source.
+    if event.mask.contains(AddWatchFlags::IN_MOVED_TO) && event.cookie != 0 {
+      if let Some(rename) = pending
+        .iter_mut()
+        .find(|e| e.kind == "rename" && e.cookie == event.cookie)
+      {
+        rename.paths.push(path);
+        return;
+      }
+    }
+
+    let fs_event = FsEvent {
+      kind,
+      paths: vec![path],
+      cookie: event.cookie,
+    };
+    if !pending.contains(&fs_event) {
+      pending.push(fs_event);
+    }
+  }
+
+  /// Reads events until `shutdown` is closed or the receiver is dropped.
+  /// Events are sent in batches, `DEBOUNCE_MS` after the first of them
+  /// arrived, even if more keep arriving.
+  fn run(
+    mut self,
+    shutdown: RawFd,
+    sender: mpsc::UnboundedSender<Vec<FsEvent>>,
+  ) {
+    let mut pending = Vec::new();
+    // When the first of the pending events arrived.
+    let mut pending_since = Instant::now();
+    loop {
+      let timeout = if pending.is_empty() {
+        -1
+      } else {
+        let elapsed = pending_since.elapsed().as_millis();
+        DEBOUNCE_MS - elapsed.min(DEBOUNCE_MS as u128) as libc::c_int
+      };
+      if timeout == 0 {
+        let batch = std::mem::replace(&mut pending, Vec::new());
+        if sender.unbounded_send(batch).is_err() {
+          break;
+        }
+        continue;
+      }
+      let mut fds = [
+        PollFd::new(self.inotify.as_raw_fd(), PollFlags::POLLIN),
+        PollFd::new(shutdown, PollFlags::POLLIN),
+      ];
+      match poll(&mut fds, timeout) {
+        Ok(0) => continue,
+        Ok(_) => {}
+        Err(nix::Error::Sys(Errno::EINTR)) => continue,
+        Err(err) => {
+          debug!("Failed to poll inotify: {}", err);
+          break;
+        }
+      }
+      if fds[1].revents().map_or(false, |r| !r.is_empty()) {
+        break;
+      }
+      if pending.is_empty() {
+        pending_since = Instant::now();
+      }
+      match self.inotify.read_events() {
+        Ok(events) => {
+          for event in events {
+            self.handle(event, &mut pending);
+          }
+        }
+        Err(nix::Error::Sys(Errno::EAGAIN)) => {}
+        Err(err) => {
+          debug!("Failed to read inotify events: {}", err);
+          break;
+        }
+      }
+    }
+    let _ = nix::unistd::close(self.inotify.as_raw_fd());
+    let _ = nix::unistd::close(shutdown);
+  }
+}
+
+#[derive(Deserialize)]
+struct FsEventsOpenArgs {
+  paths: Vec<String>,
+  recursive: bool,
+}
+
+/// Starts watching the paths for changes, read with `op_fs_events_poll`.
+#[cfg(target_os = "linux")]
+fn op_fs_events_open(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FsEventsOpenArgs = serde_json::from_value(args)?;
+  let mut resolved_paths = Vec::new();
+  for path in &args.paths {
+    let (path, path_) = deno_fs::resolve_from_cwd(path)?;
+    state.check_read(&path_)?;
+    resolved_paths.push(path);
+  }
+
+  let mut watcher = Watcher::new(args.recursive)?;
+  for path in &resolved_paths {
+    if let Err(err) = watcher.add(path) {
+      let _ = nix::unistd::close(watcher.inotify.as_raw_fd());
+      return Err(err);
+    }
+  }
+  let (shutdown_read, shutdown_write) =
+    match nix::unistd::pipe2(nix::fcntl::OFlag::O_CLOEXEC) {
+      Ok(fds) => fds,
+      Err(err) => {
+        let _ = nix::unistd::close(watcher.inotify.as_raw_fd());
+        return Err(err.into());
+      }
+    };
+  let (sender, receiver) = mpsc::unbounded();
+  std::thread::spawn(move || watcher.run(shutdown_read, sender));
+
+  let mut table = state.lock_resource_table();
+  let rid = table.add(
+    "fsEvents",
+    Box::new(FsEventsResource {
+      receiver,
+      shutdown: shutdown_write,
+      waker: AtomicWaker::new(),
+    }),
+  );
+  Ok(JsonOp::Sync(json!({
+    "rid": rid,
+  })))
+}
+
+#[derive(Deserialize)]
+struct FsEventsPollArgs {
+  rid: i32,
+}
+
+/// Resolves with the next batch of events, or with `done: true` once the
+/// watcher has been closed.
+#[cfg(target_os = "linux")]
+fn op_fs_events_poll(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FsEventsPollArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+
+  state
+    .lock_resource_table()
+    .get::<FsEventsResource>(rid)
+    .ok_or_else(bad_resource)?;
+
+  let state_ = state.clone();
+  let future = futures::future::poll_fn(move |cx| {
+    let mut table = state_.lock_resource_table();
+    match table.get_mut::<FsEventsResource>(rid) {
+      Some(watcher) => {
+        watcher.waker.register(cx.waker());
+        watcher.receiver.poll_next_unpin(cx)
+      }
+      None => Poll::Ready(None),
+    }
+  })
+  .map(|batch| -> Result<Value, ErrBox> {
+    match batch {
+      Some(events) => {
+        let events: Vec<Value> = events.iter().map(FsEvent::to_json).collect();
+        Ok(json!({ "done": false, "events": events }))
+      }
+      None => Ok(json!({ "done": true })),
+    }
+  });
+
+  Ok(JsonOp::Async(future.boxed()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn fs_events_not_supported() -> ErrBox {
+  DenoError::new(
+    ErrorKind::Other,
+    "Watching files is only supported on Linux".to_string(),
+  )
+  .into()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn op_fs_events_open(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: FsEventsOpenArgs = serde_json::from_value(args)?;
+  for path in &args.paths {
+    let (_, path_) = deno_fs::resolve_from_cwd(path)?;
+    state.check_read(&path_)?;
+  }
+  let _recursive = args.recursive;
+  Err(fs_events_not_supported())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn op_fs_events_poll(
+  _state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let _args: FsEventsPollArgs = serde_json::from_value(args)?;
+  Err(fs_events_not_supported())
+}
@@ -0,0 +1,262 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use super::dispatch_json::{Deserialize, JsonOp, Value};
+use super::io::{ResourceBody, StreamResource};
+use super::net::accept;
+use super::net::TcpListenerResource;
+use crate::deno_error::bad_resource;
+use crate::deno_error::DenoError;
+use crate::deno_error::ErrorKind;
+use crate::http_body::HttpBody;
+use crate::state::ThreadSafeState;
+use deno::*;
+use futures::channel::mpsc;
+use futures::channel::oneshot;
+use futures::future::FutureExt;
+use futures::future::TryFutureExt;
+use futures::stream::StreamExt;
+use futures::stream::TryStreamExt;
+use futures::task::AtomicWaker;
+use http::header::HeaderName;
+use http::header::HeaderValue;
+use http::StatusCode;
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::Body;
+use hyper::Request;
+use hyper::Response;
+use std;
+use std::convert::From;
+use std::task::Poll;
+use tokio;
+
+pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
+  s.register_json_op(i, "http_serve", op_http_serve);
+  s.register_json_op(i, "http_next_request", op_http_next_request);
+  s.register_json_op(i, "http_respond", op_http_respond);
+}
+
+/// A request received by hyper, waiting to be picked up by JS.
+struct PendingRequest {
+  request: Request<Body>,
+  remote_addr: String,
+  respond: oneshot::Sender<Response<Body>>,
+}
+
+/// Requests received on all connections of a listener.
+struct HttpServerResource {
+  requests: mpsc::UnboundedReceiver<PendingRequest>,
+  waker: AtomicWaker,
+}
+
+impl Resource for HttpServerResource {}
+
+impl Drop for HttpServerResource {
+  /// Lets a pending `op_http_next_request` resolve when the server is closed.
+  fn drop(&mut self) {
+    self.waker.wake();
+  }
+}
+
+/// Sends the response to a request, taken by `op_http_respond`.
+struct HttpResponderResource(Option<oneshot::Sender<Response<Body>>>);
+
+impl Resource for HttpResponderResource {}
+
+/// Answer for requests that JS did not respond to, either because the server
+/// was closed or because the responder was dropped.
+fn service_unavailable() -> Response<Body> {
+  let mut response = Response::new(Body::empty());
+  *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+  response
+}
+
+#[derive(Deserialize)]
+struct HttpServeArgs {
+  rid: i32,
+}
+
+/// Serves HTTP on the connections accepted from a TCP listener. Requests are
+/// read with `op_http_next_request`.
+fn op_http_serve(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: HttpServeArgs = serde_json::from_value(args)?;
+  let listener_rid = args.rid as u32;
+
+  let mut table = state.lock_resource_table();
+  table
+    .get::<TcpListenerResource>(listener_rid)
+    .ok_or_else(bad_resource)?;
+
+  let (sender, receiver) = mpsc::unbounded::<PendingRequest>();
+  let rid = table.add(
+    "httpServer",
+    Box::new(HttpServerResource {
+      requests: receiver,
+      waker: AtomicWaker::new(),
+    }),
+  );
+
+  let state_ = state.clone();
+  let fut = async move {
+    // Accepting stops once the listener is closed.
+    while let Ok((tcp_stream, remote_addr)) =
+      accept(&state_, listener_rid).await
+    {
+      let sender = sender.clone();
+      let remote_addr = remote_addr.to_string();
+      let service = service_fn(move |request: Request<Body>| {
+        let (respond, response) = oneshot::channel();
+        let pending = PendingRequest {
+          request,
+          remote_addr: remote_addr.clone(),
+          respond,
+        };
+        // If the server was closed the request is dropped along with the
+        // sender, which cancels the response below.
+        let _ = sender.unbounded_send(pending);
+        response
+          .map(|result| {
+            result.or_else(|canceled| {
+              debug!("HTTP request not answered: {}", canceled);
+              Ok::<_, oneshot::Canceled>(service_unavailable())
+            })
+          })
+          .compat()
+      });
+      let conn = tokio::prelude::Future::map_err(
+        Http::new().serve_connection(tcp_stream, service),
+        |e| debug!("HTTP connection error: {}", e),
+      );
+      tokio::spawn(conn);
+    }
+    Ok::<(), ()>(())
+  };
+  tokio::spawn(fut.boxed().compat());
+
+  Ok(JsonOp::Sync(json!(rid)))
+}
+
+#[derive(Deserialize)]
+struct HttpNextRequestArgs {
+  rid: i32,
+}
+
+/// Resolves to the next request, or to `null` once the server is closed.
+fn op_http_next_request(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: HttpNextRequestArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+
+  state
+    .lock_resource_table()
+    .get::<HttpServerResource>(rid)
+    .ok_or_else(bad_resource)?;
+
+  let state_ = state.clone();
+  let next = futures::future::poll_fn(move |cx| {
+    let mut table = state_.lock_resource_table();
+    match table.get_mut::<HttpServerResource>(rid) {
+      Some(server) => {
+        server.waker.register(cx.waker());
+        server.requests.poll_next_unpin(cx)
+      }
+      None => Poll::Ready(None),
+    }
+  });
+
+  let state_ = state.clone();
+  let op = next.map(move |pending| -> Result<Value, ErrBox> {
+    let pending = match pending {
+      Some(pending) => pending,
+      None => return Ok(json!(null)),
+    };
+    let (parts, body) = pending.request.into_parts();
+    let mut headers = Vec::new();
+    for (key, val) in parts.headers.iter() {
+      headers.push((key.to_string(), val.to_str().unwrap_or("").to_owned()));
+    }
+
+    let mut table = state_.lock_resource_table();
+    let body_rid = table.add(
+      "httpRequestBody",
+      Box::new(StreamResource::HttpRequestBody(Box::new(HttpBody::from(
+        body,
+      )))),
+    );
+    let request_rid = table.add(
+      "httpResponder",
+      Box::new(HttpResponderResource(Some(pending.respond))),
+    );
+
+    Ok(json!({
+      "requestRid": request_rid,
+      "bodyRid": body_rid,
+      "method": parts.method.as_str(),
+      "url": parts.uri.to_string(),
+      "headers": headers,
+      "remoteAddr": pending.remote_addr,
+    }))
+  });
+
+  Ok(JsonOp::Async(op.boxed()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpRespondArgs {
+  rid: i32,
+  status: u16,
+  headers: Vec<(String, String)>,
+  /// Resource to stream the response body from, instead of the zero copy
+  /// buffer.
+  body_rid: Option<u32>,
+}
+
+fn invalid_response(msg: &str) -> ErrBox {
+  DenoError::new(ErrorKind::InvalidInput, msg.to_string()).into()
+}
+
+/// Sends the response for a request and closes its `requestRid`.
+fn op_http_respond(
+  state: &ThreadSafeState,
+  args: Value,
+  data: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: HttpRespondArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+
+  let body = match (args.body_rid, data) {
+    (Some(body_rid), _) => {
+      Body::wrap_stream(ResourceBody::new(state, body_rid).compat())
+    }
+    (None, Some(buf)) => Body::from(Vec::from(&*buf)),
+    (None, None) => Body::empty(),
+  };
+  let mut response = Response::new(body);
+  *response.status_mut() = StatusCode::from_u16(args.status)
+    .map_err(|_| invalid_response("Invalid status code"))?;
+  for (key, value) in args.headers {
+    let name = HeaderName::from_bytes(key.as_bytes())
+      .map_err(|_| invalid_response("Invalid header name"))?;
+    let v = HeaderValue::from_str(&value)
+      .map_err(|_| invalid_response("Invalid header value"))?;
+    response.headers_mut().append(name, v);
+  }
+
+  let mut table = state.lock_resource_table();
+  let respond = table
+    .get_mut::<HttpResponderResource>(rid)
+    .and_then(|responder| responder.0.take())
+    .ok_or_else(bad_resource)?;
+  table.close(rid);
+  // The client may have gone away in the meantime, which is not an error.
+  let _ = respond.send(response);
+
+  Ok(JsonOp::Sync(json!({})))
+}
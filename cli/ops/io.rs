@@ -12,6 +12,7 @@ use futures::compat::AsyncRead01CompatExt;
 use futures::compat::AsyncWrite01CompatExt;
 use futures::future::FutureExt;
 use futures::io::{AsyncRead, AsyncWrite};
+use futures::stream::Stream;
 use std;
 use std::future::Future;
 use std::pin::Pin;
@@ -86,9 +87,15 @@ pub enum StreamResource {
   ServerTlsStream(Box<ServerTlsStream<TcpStream>>),
   ClientTlsStream(Box<ClientTlsStream<TcpStream>>),
   HttpBody(Box<HttpBody>),
+  HttpRequestBody(Box<HttpBody<hyper::Body>>),
   ChildStdin(tokio_process::ChildStdin),
   ChildStdout(tokio_process::ChildStdout),
   ChildStderr(tokio_process::ChildStderr),
+  #[cfg(unix)]
+  UnixStream(tokio::net::UnixStream),
+  /// Master side of a pseudo-terminal, and its descriptor for resizing.
+  #[cfg(unix)]
+  Pty(tokio::fs::File, std::os::unix::io::RawFd),
 }
 
 impl Resource for StreamResource {}
@@ -114,6 +121,10 @@ impl DenoAsyncRead for StreamResource {
       StreamResource::FsFile(f) => Box::new(AsyncRead01CompatExt::compat(f)),
       StreamResource::Stdin(f) => Box::new(AsyncRead01CompatExt::compat(f)),
       StreamResource::TcpStream(f) => Box::new(AsyncRead01CompatExt::compat(f)),
+      #[cfg(unix)]
+      StreamResource::UnixStream(f) => {
+        Box::new(AsyncRead01CompatExt::compat(f))
+      }
       StreamResource::ClientTlsStream(f) => {
         Box::new(AsyncRead01CompatExt::compat(f))
       }
@@ -121,6 +132,21 @@ impl DenoAsyncRead for StreamResource {
         Box::new(AsyncRead01CompatExt::compat(f))
       }
       StreamResource::HttpBody(f) => Box::new(f),
+      StreamResource::HttpRequestBody(f) => Box::new(f),
+      #[cfg(unix)]
+      StreamResource::Pty(f, _) => {
+        // Linux fails reads of the master with EIO once the slave side is
+        // closed, which is the end of the stream rather than an error.
+        let mut f = AsyncRead01CompatExt::compat(f);
+        return match AsyncRead::poll_read(Pin::new(&mut f), cx, buf) {
+          Poll::Ready(Err(ref e)) if e.raw_os_error() == Some(libc::EIO) => {
+            Poll::Ready(Ok(0))
+          }
+          Poll::Ready(Err(e)) => Poll::Ready(Err(ErrBox::from(e))),
+          Poll::Ready(Ok(v)) => Poll::Ready(Ok(v)),
+          Poll::Pending => Poll::Pending,
+        };
+      }
       StreamResource::ChildStdout(f) => {
         Box::new(AsyncRead01CompatExt::compat(f))
       }
@@ -224,6 +250,53 @@ pub fn op_read(
   fut.boxed()
 }
 
+/// Stream of chunks read from a resource, used to send HTTP bodies without
+/// buffering them in memory.
+pub struct ResourceBody {
+  state: ThreadSafeState,
+  rid: ResourceId,
+  buf: Vec<u8>,
+}
+
+impl ResourceBody {
+  pub fn new(state: &ThreadSafeState, rid: ResourceId) -> Self {
+    Self {
+      state: state.clone(),
+      rid,
+      buf: vec![0; 64 * 1024],
+    }
+  }
+}
+
+impl Stream for ResourceBody {
+  type Item = Result<Vec<u8>, std::io::Error>;
+
+  fn poll_next(
+    self: Pin<&mut Self>,
+    cx: &mut Context,
+  ) -> Poll<Option<Self::Item>> {
+    let inner = self.get_mut();
+    let mut table = inner.state.lock_resource_table();
+    let resource = match table.get_mut::<StreamResource>(inner.rid) {
+      Some(resource) => resource,
+      None => {
+        let err =
+          std::io::Error::new(std::io::ErrorKind::Other, "bad resource id");
+        return Poll::Ready(Some(Err(err)));
+      }
+    };
+    match DenoAsyncRead::poll_read(Pin::new(resource), cx, &mut inner.buf) {
+      Poll::Ready(Ok(0)) => Poll::Ready(None),
+      Poll::Ready(Ok(n)) => Poll::Ready(Some(Ok(inner.buf[..n].to_vec()))),
+      Poll::Ready(Err(err)) => Poll::Ready(Some(Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        err.to_string(),
+      )))),
+      Poll::Pending => Poll::Pending,
+    }
+  }
+}
+
 /// `DenoAsyncWrite` is the same as the `tokio_io::AsyncWrite` trait
 /// but uses an `ErrBox` error instead of `std::io:Error`
 pub trait DenoAsyncWrite {
@@ -249,10 +322,16 @@ impl DenoAsyncWrite for StreamResource {
     let mut f: Box<dyn AsyncWrite + Unpin> = match inner {
       StreamResource::FsFile(f) => Box::new(AsyncWrite01CompatExt::compat(f)),
       StreamResource::Stdout(f) => Box::new(AsyncWrite01CompatExt::compat(f)),
+      #[cfg(unix)]
+      StreamResource::Pty(f, _) => Box::new(AsyncWrite01CompatExt::compat(f)),
       StreamResource::Stderr(f) => Box::new(AsyncWrite01CompatExt::compat(f)),
       StreamResource::TcpStream(f) => {
         Box::new(AsyncWrite01CompatExt::compat(f))
       }
+      #[cfg(unix)]
+      StreamResource::UnixStream(f) => {
+        Box::new(AsyncWrite01CompatExt::compat(f))
+      }
       StreamResource::ClientTlsStream(f) => {
         Box::new(AsyncWrite01CompatExt::compat(f))
       }
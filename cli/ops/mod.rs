@@ -12,6 +12,8 @@ pub mod errors;
 pub mod fetch;
 pub mod files;
 pub mod fs;
+pub mod fs_events;
+pub mod http;
 pub mod io;
 pub mod net;
 pub mod os;
@@ -21,6 +23,7 @@ pub mod process;
 pub mod random;
 pub mod repl;
 pub mod resources;
+pub mod signal;
 pub mod timers;
 pub mod tls;
 pub mod workers;
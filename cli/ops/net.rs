@@ -1,8 +1,11 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{Deserialize, JsonOp, Value};
 use super::io::StreamResource;
+use crate::deno_error;
 use crate::deno_error::bad_resource;
-use crate::ops::json_op;
+use crate::deno_error::type_error;
+#[cfg(unix)]
+use crate::fs as deno_fs;
 use crate::resolve_addr::resolve_addr;
 use crate::state::ThreadSafeState;
 use deno::Resource;
@@ -23,12 +26,19 @@ use tokio;
 use tokio::net::tcp::Incoming;
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
+use tokio::net::UdpSocket;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+#[cfg(unix)]
+use tokio::net::UnixStream;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("accept", s.core_op(json_op(s.stateful_op(op_accept))));
-  i.register_op("dial", s.core_op(json_op(s.stateful_op(op_dial))));
-  i.register_op("shutdown", s.core_op(json_op(s.stateful_op(op_shutdown))));
-  i.register_op("listen", s.core_op(json_op(s.stateful_op(op_listen))));
+  s.register_json_op(i, "accept", op_accept);
+  s.register_json_op(i, "dial", op_dial);
+  s.register_json_op(i, "shutdown", op_shutdown);
+  s.register_json_op(i, "listen", op_listen);
+  s.register_json_op(i, "send_to", op_send_to);
+  s.register_json_op(i, "recv_from", op_recv_from);
 }
 
 #[derive(Debug, PartialEq)]
@@ -110,6 +120,16 @@ fn op_accept(
 ) -> Result<JsonOp, ErrBox> {
   let args: AcceptArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
+  #[cfg(unix)]
+  {
+    let is_unix_listener = state
+      .lock_resource_table()
+      .get::<UnixListenerResource>(rid)
+      .is_some();
+    if is_unix_listener {
+      return accept_unix(state, rid);
+    }
+  }
   let state_ = state.clone();
   let table = state.lock_resource_table();
   table
@@ -144,8 +164,12 @@ fn op_accept(
 }
 
 #[derive(Deserialize)]
-struct DialArgs {
+struct TransportArgs {
   transport: String,
+}
+
+#[derive(Deserialize)]
+struct DialArgs {
   hostname: String,
   port: u16,
 }
@@ -155,8 +179,16 @@ fn op_dial(
   args: Value,
   _zero_copy: Option<PinnedBuf>,
 ) -> Result<JsonOp, ErrBox> {
-  let args: DialArgs = serde_json::from_value(args)?;
-  assert_eq!(args.transport, "tcp"); // TODO Support others.
+  let transport_args: TransportArgs = serde_json::from_value(args.clone())?;
+  match transport_args.transport.as_str() {
+    "tcp" => dial_tcp(state, serde_json::from_value(args)?),
+    #[cfg(unix)]
+    "unix" => dial_unix(state, serde_json::from_value(args)?),
+    transport => Err(unsupported_transport(transport)),
+  }
+}
+
+fn dial_tcp(state: &ThreadSafeState, args: DialArgs) -> Result<JsonOp, ErrBox> {
   let state_ = state.clone();
   state.check_net(&args.hostname, args.port)?;
 
@@ -220,6 +252,10 @@ fn op_shutdown(
     StreamResource::TcpStream(ref mut stream) => {
       TcpStream::shutdown(stream, shutdown_mode).map_err(ErrBox::from)?;
     }
+    #[cfg(unix)]
+    StreamResource::UnixStream(ref mut stream) => {
+      UnixStream::shutdown(stream, shutdown_mode).map_err(ErrBox::from)?;
+    }
     _ => return Err(bad_resource()),
   }
 
@@ -228,13 +264,12 @@ fn op_shutdown(
 
 #[derive(Deserialize)]
 struct ListenArgs {
-  transport: String,
   hostname: String,
   port: u16,
 }
 
 #[allow(dead_code)]
-struct TcpListenerResource {
+pub struct TcpListenerResource {
   listener: Incoming,
   waker: Option<futures::task::AtomicWaker>,
   local_addr: SocketAddr,
@@ -293,9 +328,20 @@ fn op_listen(
   args: Value,
   _zero_copy: Option<PinnedBuf>,
 ) -> Result<JsonOp, ErrBox> {
-  let args: ListenArgs = serde_json::from_value(args)?;
-  assert_eq!(args.transport, "tcp");
+  let transport_args: TransportArgs = serde_json::from_value(args.clone())?;
+  match transport_args.transport.as_str() {
+    "tcp" => listen_tcp(state, serde_json::from_value(args)?),
+    "udp" => listen_udp(state, serde_json::from_value(args)?),
+    #[cfg(unix)]
+    "unix" => listen_unix(state, serde_json::from_value(args)?),
+    transport => Err(unsupported_transport(transport)),
+  }
+}
 
+fn listen_tcp(
+  state: &ThreadSafeState,
+  args: ListenArgs,
+) -> Result<JsonOp, ErrBox> {
   state.check_net(&args.hostname, args.port)?;
 
   let addr =
@@ -317,3 +363,377 @@ fn op_listen(
     "localAddr": local_addr_str,
   })))
 }
+
+struct UdpSocketResource {
+  socket: UdpSocket,
+  waker: Option<futures::task::AtomicWaker>,
+}
+
+impl Resource for UdpSocketResource {}
+
+impl Drop for UdpSocketResource {
+  fn drop(&mut self) {
+    self.wake_task();
+  }
+}
+
+impl UdpSocketResource {
+  /// Track the current task so future awaiting for a datagram
+  /// can be notified when socket is closed.
+  ///
+  /// Throws an error if another task is already tracked.
+  pub fn track_task(&mut self, cx: &Context) -> Result<(), ErrBox> {
+    // Like TcpListener, UdpSocket only tracks a single receive task at a time.
+    if self.waker.is_some() {
+      let e = std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "Another receive task is ongoing",
+      );
+      return Err(ErrBox::from(e));
+    }
+
+    let waker = futures::task::AtomicWaker::new();
+    waker.register(cx.waker());
+    self.waker.replace(waker);
+    Ok(())
+  }
+
+  /// Notifies a task when socket is closed so receive future can resolve.
+  pub fn wake_task(&mut self) {
+    if let Some(waker) = self.waker.as_ref() {
+      waker.wake();
+    }
+  }
+
+  /// Stop tracking a task.
+  /// Happens when the task is done and thus no further tracking is needed.
+  pub fn untrack_task(&mut self) {
+    if self.waker.is_some() {
+      self.waker.take();
+    }
+  }
+}
+
+fn listen_udp(
+  state: &ThreadSafeState,
+  args: ListenArgs,
+) -> Result<JsonOp, ErrBox> {
+  state.check_net(&args.hostname, args.port)?;
+
+  let addr =
+    futures::executor::block_on(resolve_addr(&args.hostname, args.port))?;
+  let socket = UdpSocket::bind(&addr)?;
+  let local_addr_str = socket.local_addr()?.to_string();
+  let socket_resource = UdpSocketResource {
+    socket,
+    waker: None,
+  };
+  let mut table = state.lock_resource_table();
+  let rid = table.add("udpSocket", Box::new(socket_resource));
+  debug!("New udp socket {} {}", rid, local_addr_str);
+
+  Ok(JsonOp::Sync(json!({
+    "rid": rid,
+    "localAddr": local_addr_str,
+  })))
+}
+
+#[cfg(unix)]
+#[derive(Deserialize)]
+struct UnixArgs {
+  path: String,
+}
+
+#[cfg(unix)]
+fn unix_addr_to_string(addr: &std::os::unix::net::SocketAddr) -> String {
+  match addr.as_pathname() {
+    Some(path) => path.display().to_string(),
+    None => String::new(),
+  }
+}
+
+/// Path-based sockets are gated by file system permissions rather than net
+/// permissions, as connecting to one requires both read and write access.
+#[cfg(unix)]
+fn check_unix_path(
+  state: &ThreadSafeState,
+  path: &str,
+) -> Result<std::path::PathBuf, ErrBox> {
+  let (path, path_) = deno_fs::resolve_from_cwd(path)?;
+  state.check_read(&path_)?;
+  state.check_write(&path_)?;
+  Ok(path)
+}
+
+#[cfg(unix)]
+struct UnixListenerResource {
+  listener: UnixListener,
+  waker: Option<futures::task::AtomicWaker>,
+}
+
+#[cfg(unix)]
+impl Resource for UnixListenerResource {}
+
+#[cfg(unix)]
+impl Drop for UnixListenerResource {
+  fn drop(&mut self) {
+    self.wake_task();
+  }
+}
+
+#[cfg(unix)]
+impl UnixListenerResource {
+  /// Track the current task so future awaiting for connection
+  /// can be notified when listener is closed.
+  ///
+  /// Throws an error if another task is already tracked.
+  pub fn track_task(&mut self, cx: &Context) -> Result<(), ErrBox> {
+    // Like TcpListener, UnixListener only tracks a single accept task at a
+    // time.
+    if self.waker.is_some() {
+      let e = std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "Another accept task is ongoing",
+      );
+      return Err(ErrBox::from(e));
+    }
+
+    let waker = futures::task::AtomicWaker::new();
+    waker.register(cx.waker());
+    self.waker.replace(waker);
+    Ok(())
+  }
+
+  /// Notifies a task when listener is closed so accept future can resolve.
+  pub fn wake_task(&mut self) {
+    if let Some(waker) = self.waker.as_ref() {
+      waker.wake();
+    }
+  }
+
+  /// Stop tracking a task.
+  /// Happens when the task is done and thus no further tracking is needed.
+  pub fn untrack_task(&mut self) {
+    if self.waker.is_some() {
+      self.waker.take();
+    }
+  }
+}
+
+#[cfg(unix)]
+fn listen_unix(
+  state: &ThreadSafeState,
+  args: UnixArgs,
+) -> Result<JsonOp, ErrBox> {
+  let path = check_unix_path(state, &args.path)?;
+  let listener = UnixListener::bind(&path)?;
+  let local_addr_str = unix_addr_to_string(&listener.local_addr()?);
+  let listener_resource = UnixListenerResource {
+    listener,
+    waker: None,
+  };
+  let mut table = state.lock_resource_table();
+  let rid = table.add("unixListener", Box::new(listener_resource));
+  debug!("New unix listener {} {}", rid, local_addr_str);
+
+  Ok(JsonOp::Sync(json!({
+    "rid": rid,
+    "localAddr": local_addr_str,
+  })))
+}
+
+#[cfg(unix)]
+fn add_unix_stream(
+  state: &ThreadSafeState,
+  unix_stream: UnixStream,
+) -> Result<Value, ErrBox> {
+  let local_addr = unix_addr_to_string(&unix_stream.local_addr()?);
+  let remote_addr = unix_addr_to_string(&unix_stream.peer_addr()?);
+  let mut table = state.lock_resource_table();
+  let rid = table.add(
+    "unixStream",
+    Box::new(StreamResource::UnixStream(unix_stream)),
+  );
+  Ok(json!({
+    "rid": rid,
+    "localAddr": local_addr,
+    "remoteAddr": remote_addr,
+  }))
+}
+
+#[cfg(unix)]
+fn accept_unix(
+  state: &ThreadSafeState,
+  rid: ResourceId,
+) -> Result<JsonOp, ErrBox> {
+  let state_ = state.clone();
+  let accept_state = state.clone();
+
+  let op = futures::future::poll_fn(
+    move |cx: &mut Context| -> Poll<Result<(UnixStream, _), ErrBox>> {
+      let mut table = accept_state.lock_resource_table();
+      let listener_resource =
+        table.get_mut::<UnixListenerResource>(rid).ok_or_else(|| {
+          let e = std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Listener has been closed",
+          );
+          ErrBox::from(e)
+        })?;
+      let poll = {
+        let listener = &mut listener_resource.listener;
+        futures::compat::Compat01As03::new(tokio::prelude::future::poll_fn(
+          move || listener.poll_accept(),
+        ))
+        .poll_unpin(cx)
+      };
+      match poll {
+        Poll::Pending => {
+          listener_resource.track_task(cx)?;
+          Poll::Pending
+        }
+        Poll::Ready(result) => {
+          listener_resource.untrack_task();
+          Poll::Ready(result.map_err(ErrBox::from))
+        }
+      }
+    },
+  )
+  .and_then(move |(unix_stream, _socket_addr)| {
+    futures::future::ready(add_unix_stream(&state_, unix_stream))
+  });
+
+  Ok(JsonOp::Async(op.boxed()))
+}
+
+#[cfg(unix)]
+fn dial_unix(
+  state: &ThreadSafeState,
+  args: UnixArgs,
+) -> Result<JsonOp, ErrBox> {
+  let path = check_unix_path(state, &args.path)?;
+  let state_ = state.clone();
+
+  let op = futures::compat::Compat01As03::new(UnixStream::connect(path))
+    .map_err(ErrBox::from)
+    .and_then(move |unix_stream| {
+      futures::future::ready(add_unix_stream(&state_, unix_stream))
+    });
+
+  Ok(JsonOp::Async(op.boxed()))
+}
+
+fn unsupported_transport(transport: &str) -> ErrBox {
+  type_error(format!("Unsupported transport protocol: {}", transport))
+}
+
+fn socket_closed() -> ErrBox {
+  let e =
+    std::io::Error::new(std::io::ErrorKind::Other, "Socket has been closed");
+  ErrBox::from(e)
+}
+
+#[derive(Deserialize)]
+struct SendToArgs {
+  rid: i32,
+  hostname: String,
+  port: u16,
+}
+
+/// Sends the zero-copy buffer as a single datagram to the given peer.
+fn op_send_to(
+  state: &ThreadSafeState,
+  args: Value,
+  zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: SendToArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+  let buf = zero_copy.ok_or_else(deno_error::no_buffer_specified)?;
+  state.check_net(&args.hostname, args.port)?;
+
+  let state_ = state.clone();
+  {
+    let table = state.lock_resource_table();
+    table
+      .get::<UdpSocketResource>(rid)
+      .ok_or_else(bad_resource)?;
+  }
+
+  let op = resolve_addr(&args.hostname, args.port).and_then(move |addr| {
+    futures::compat::Compat01As03::new(tokio::prelude::future::poll_fn(
+      move || {
+        let mut table = state_.lock_resource_table();
+        let resource = table
+          .get_mut::<UdpSocketResource>(rid)
+          .ok_or_else(socket_closed)?;
+        resource
+          .socket
+          .poll_send_to(&buf, &addr)
+          .map_err(ErrBox::from)
+      },
+    ))
+    .and_then(move |size| futures::future::ok(json!({ "size": size })))
+  });
+
+  Ok(JsonOp::Async(op.boxed()))
+}
+
+#[derive(Deserialize)]
+struct RecvFromArgs {
+  rid: i32,
+}
+
+/// Receives a single datagram into the zero-copy buffer. The number of bytes
+/// read and the address of the sender are carried in the JSON response.
+fn op_recv_from(
+  state: &ThreadSafeState,
+  args: Value,
+  zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: RecvFromArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+  let mut buf = zero_copy.ok_or_else(deno_error::no_buffer_specified)?;
+
+  let state_ = state.clone();
+  {
+    let table = state.lock_resource_table();
+    table
+      .get::<UdpSocketResource>(rid)
+      .ok_or_else(bad_resource)?;
+  }
+
+  let op = futures::future::poll_fn(
+    move |cx: &mut Context| -> Poll<Result<(usize, SocketAddr), ErrBox>> {
+      let mut table = state_.lock_resource_table();
+      let resource = table
+        .get_mut::<UdpSocketResource>(rid)
+        .ok_or_else(socket_closed)?;
+      let poll = {
+        let socket = &mut resource.socket;
+        let buf = &mut buf;
+        futures::compat::Compat01As03::new(tokio::prelude::future::poll_fn(
+          move || socket.poll_recv_from(buf),
+        ))
+        .poll_unpin(cx)
+      };
+      match poll {
+        Poll::Pending => {
+          resource.track_task(cx)?;
+          Poll::Pending
+        }
+        Poll::Ready(result) => {
+          resource.untrack_task();
+          Poll::Ready(result.map_err(ErrBox::from))
+        }
+      }
+    },
+  )
+  .and_then(move |(size, remote_addr)| {
+    futures::future::ok(json!({
+      "size": size,
+      "remoteAddr": remote_addr.to_string(),
+    }))
+  });
+
+  Ok(JsonOp::Async(op.boxed()))
+}
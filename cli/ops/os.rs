@@ -2,8 +2,9 @@
 use super::dispatch_json::{Deserialize, JsonOp, Value};
 use crate::colors;
 use crate::fs as deno_fs;
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
+#[cfg(unix)]
+use crate::tty;
 use crate::version;
 use atty;
 use deno::*;
@@ -12,6 +13,13 @@ use std::env;
 use sys_info;
 use url::Url;
 
+#[cfg(unix)]
+use super::io::StreamResource;
+#[cfg(unix)]
+use crate::deno_error::bad_resource;
+#[cfg(not(unix))]
+use crate::deno_error::{DenoError, ErrorKind};
+
 /// BUILD_OS and BUILD_ARCH match the values in Deno.build. See js/build.ts.
 #[cfg(target_os = "macos")]
 static BUILD_OS: &str = "mac";
@@ -23,15 +31,17 @@ static BUILD_OS: &str = "win";
 static BUILD_ARCH: &str = "x64";
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("exit", s.core_op(json_op(s.stateful_op(op_exit))));
-  i.register_op("is_tty", s.core_op(json_op(s.stateful_op(op_is_tty))));
-  i.register_op("env", s.core_op(json_op(s.stateful_op(op_env))));
-  i.register_op("exec_path", s.core_op(json_op(s.stateful_op(op_exec_path))));
-  i.register_op("set_env", s.core_op(json_op(s.stateful_op(op_set_env))));
-  i.register_op("get_env", s.core_op(json_op(s.stateful_op(op_get_env))));
-  i.register_op("home_dir", s.core_op(json_op(s.stateful_op(op_home_dir))));
-  i.register_op("hostname", s.core_op(json_op(s.stateful_op(op_hostname))));
-  i.register_op("start", s.core_op(json_op(s.stateful_op(op_start))));
+  s.register_json_op(i, "exit", op_exit);
+  s.register_json_op(i, "is_tty", op_is_tty);
+  s.register_json_op(i, "set_raw", op_set_raw);
+  s.register_json_op(i, "console_size", op_console_size);
+  s.register_json_op(i, "env", op_env);
+  s.register_json_op(i, "exec_path", op_exec_path);
+  s.register_json_op(i, "set_env", op_set_env);
+  s.register_json_op(i, "get_env", op_get_env);
+  s.register_json_op(i, "home_dir", op_home_dir);
+  s.register_json_op(i, "hostname", op_hostname);
+  s.register_json_op(i, "start", op_start);
 }
 
 fn op_start(
@@ -97,7 +107,7 @@ fn op_set_env(
   _zero_copy: Option<PinnedBuf>,
 ) -> Result<JsonOp, ErrBox> {
   let args: SetEnv = serde_json::from_value(args)?;
-  state.check_env()?;
+  state.check_env_var(&args.key)?;
   env::set_var(args.key, args.value);
   Ok(JsonOp::Sync(json!({})))
 }
@@ -107,8 +117,15 @@ fn op_env(
   _args: Value,
   _zero_copy: Option<PinnedBuf>,
 ) -> Result<JsonOp, ErrBox> {
-  state.check_env()?;
-  let v = env::vars().collect::<HashMap<String, String>>();
+  let whitelist = state.permissions.lock().unwrap().env_whitelist.clone();
+  let v = match state.check_env() {
+    Ok(()) => env::vars().collect::<HashMap<String, String>>(),
+    // Only the whitelisted variables are visible.
+    Err(_) if !whitelist.is_empty() => env::vars()
+      .filter(|(key, _)| whitelist.contains(key))
+      .collect::<HashMap<String, String>>(),
+    Err(err) => return Err(err),
+  };
   Ok(JsonOp::Sync(json!(v)))
 }
 
@@ -123,7 +140,7 @@ fn op_get_env(
   _zero_copy: Option<PinnedBuf>,
 ) -> Result<JsonOp, ErrBox> {
   let args: GetEnv = serde_json::from_value(args)?;
-  state.check_env()?;
+  state.check_env_var(&args.key)?;
   let r = match env::var(args.key) {
     Err(env::VarError::NotPresent) => json!([]),
     v => json!([v?]),
@@ -157,6 +174,89 @@ fn op_is_tty(
   })))
 }
 
+/// Descriptor of a resource that may refer to a terminal.
+#[cfg(unix)]
+fn tty_fd(
+  state: &ThreadSafeState,
+  rid: u32,
+) -> Result<std::os::unix::io::RawFd, ErrBox> {
+  let table = state.lock_resource_table();
+  match table.get::<StreamResource>(rid) {
+    Some(StreamResource::Stdin(_)) => Ok(libc::STDIN_FILENO),
+    Some(StreamResource::Stdout(_)) => Ok(libc::STDOUT_FILENO),
+    Some(StreamResource::Stderr(_)) => Ok(libc::STDERR_FILENO),
+    Some(StreamResource::Pty(_, fd)) => Ok(*fd),
+    _ => Err(bad_resource()),
+  }
+}
+
+#[derive(Deserialize)]
+struct SetRawArgs {
+  rid: i32,
+  mode: bool,
+}
+
+#[cfg(unix)]
+fn op_set_raw(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: SetRawArgs = serde_json::from_value(args)?;
+  let fd = tty_fd(state, args.rid as u32)?;
+  tty::set_raw(fd, args.mode)?;
+  Ok(JsonOp::Sync(json!({})))
+}
+
+#[cfg(not(unix))]
+fn op_set_raw(
+  _state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let _args: SetRawArgs = serde_json::from_value(args)?;
+  Err(tty_not_supported())
+}
+
+#[derive(Deserialize)]
+struct ConsoleSizeArgs {
+  rid: i32,
+}
+
+#[cfg(unix)]
+fn op_console_size(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: ConsoleSizeArgs = serde_json::from_value(args)?;
+  let fd = tty_fd(state, args.rid as u32)?;
+  let (columns, rows) = tty::console_size(fd)?;
+  Ok(JsonOp::Sync(json!({
+    "columns": columns,
+    "rows": rows,
+  })))
+}
+
+#[cfg(not(unix))]
+fn op_console_size(
+  _state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let _args: ConsoleSizeArgs = serde_json::from_value(args)?;
+  Err(tty_not_supported())
+}
+
+#[cfg(not(unix))]
+fn tty_not_supported() -> ErrBox {
+  DenoError::new(
+    ErrorKind::Other,
+    "Terminal modes are only supported on Unix".to_string(),
+  )
+  .into()
+}
+
 fn op_hostname(
   state: &ThreadSafeState,
   _args: Value,
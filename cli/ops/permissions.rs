@@ -1,23 +1,14 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{Deserialize, JsonOp, Value};
 use crate::deno_error::type_error;
-use crate::ops::json_op;
+use crate::permissions::PermissionState;
 use crate::state::ThreadSafeState;
 use deno::*;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op(
-    "query_permission",
-    s.core_op(json_op(s.stateful_op(op_query_permission))),
-  );
-  i.register_op(
-    "revoke_permission",
-    s.core_op(json_op(s.stateful_op(op_revoke_permission))),
-  );
-  i.register_op(
-    "request_permission",
-    s.core_op(json_op(s.stateful_op(op_request_permission))),
-  );
+  s.register_json_op(i, "query_permission", op_query_permission);
+  s.register_json_op(i, "revoke_permission", op_revoke_permission);
+  s.register_json_op(i, "request_permission", op_request_permission);
 }
 
 #[derive(Deserialize)]
@@ -73,20 +64,28 @@ pub fn op_request_permission(
   _zero_copy: Option<PinnedBuf>,
 ) -> Result<JsonOp, ErrBox> {
   let args: PermissionArgs = serde_json::from_value(args)?;
+  let url = args.url.as_ref().map(String::as_str);
+  let path = args.path.as_ref().map(String::as_str);
   let mut permissions = state.permissions.lock().unwrap();
+  let prompted = permissions.get_permission_state(&args.name, &url, &path)?
+    == PermissionState::Ask;
   let perm = match args.name.as_ref() {
     "run" => Ok(permissions.request_run()),
-    "read" => {
-      Ok(permissions.request_read(&args.path.as_ref().map(String::as_str)))
-    }
-    "write" => {
-      Ok(permissions.request_write(&args.path.as_ref().map(String::as_str)))
-    }
-    "net" => permissions.request_net(&args.url.as_ref().map(String::as_str)),
+    "read" => Ok(permissions.request_read(&path)),
+    "write" => Ok(permissions.request_write(&path)),
+    "net" => permissions.request_net(&url),
     "env" => Ok(permissions.request_env()),
     "plugin" => Ok(permissions.request_plugin()),
     "hrtime" => Ok(permissions.request_hrtime()),
     n => Err(type_error(format!("No such permission name: {}", n))),
   }?;
+  if let Some(log) = &state.global_state.permission_log {
+    let target = path.or(url);
+    if prompted {
+      log.write(&args.name, target, "prompted", Some("request_permission"));
+    }
+    let result = perm.to_string();
+    log.write(&args.name, target, &result, Some("request_permission"));
+  }
   Ok(JsonOp::Sync(json!({ "state": perm.to_string() })))
 }
@@ -1,6 +1,5 @@
 use super::dispatch_json::{Deserialize, JsonOp, Value};
 use crate::fs as deno_fs;
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::*;
 use dlopen::symbor::Library;
@@ -10,12 +9,9 @@ use std::sync::Arc;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState, r: Arc<deno::OpRegistry>) {
   let r_ = r.clone();
-  i.register_op(
-    "open_plugin",
-    s.core_op(json_op(s.stateful_op(move |state, args, zero_copy| {
-      op_open_plugin(&r_, state, args, zero_copy)
-    }))),
-  );
+  s.register_json_op(i, "open_plugin", move |state, args, zero_copy| {
+    op_open_plugin(&r_, state, args, zero_copy)
+  });
 }
 
 fn open_plugin<P: AsRef<OsStr>>(lib_path: P) -> Result<Library, ErrBox> {
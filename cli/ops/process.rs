@@ -2,7 +2,10 @@
 use super::dispatch_json::{Deserialize, JsonOp, Value};
 use super::io::StreamResource;
 use crate::deno_error::bad_resource;
-use crate::ops::json_op;
+#[cfg(not(unix))]
+use crate::deno_error::{DenoError, ErrorKind};
+#[cfg(unix)]
+use crate::pty::{set_controlling_terminal, Pty};
 use crate::signal::kill;
 use crate::state::ThreadSafeState;
 use deno::*;
@@ -21,16 +24,16 @@ use std::task::Poll;
 use tokio::prelude::Async;
 use tokio_process::CommandExt;
 
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("run", s.core_op(json_op(s.stateful_op(op_run))));
-  i.register_op(
-    "run_status",
-    s.core_op(json_op(s.stateful_op(op_run_status))),
-  );
-  i.register_op("kill", s.core_op(json_op(s.stateful_op(op_kill))));
+  s.register_json_op(i, "run", op_run);
+  s.register_json_op(i, "run_status", op_run_status);
+  s.register_json_op(i, "kill", op_kill);
+  s.register_json_op(i, "pty_resize", op_pty_resize);
 }
 
 struct CloneFileFuture {
@@ -71,11 +74,40 @@ fn clone_file(
   .map(|f| f.into_std())
 }
 
-fn subprocess_stdio_map(s: &str) -> std::process::Stdio {
+/// Pseudo-terminals are only supported on Unix.
+#[cfg(not(unix))]
+enum Pty {}
+
+#[cfg(not(unix))]
+impl Pty {
+  fn open() -> Result<Self, ErrBox> {
+    Err(
+      DenoError::new(
+        ErrorKind::Other,
+        "\"pty\" stdio is only supported on Unix".to_string(),
+      )
+      .into(),
+    )
+  }
+
+  fn slave_stdio(&self) -> Result<std::process::Stdio, ErrBox> {
+    match *self {}
+  }
+
+  fn into_master(self) -> std::fs::File {
+    match self {}
+  }
+}
+
+fn subprocess_stdio_map(
+  s: &str,
+  pty: Option<&Pty>,
+) -> Result<std::process::Stdio, ErrBox> {
   match s {
-    "inherit" => std::process::Stdio::inherit(),
-    "piped" => std::process::Stdio::piped(),
-    "null" => std::process::Stdio::null(),
+    "inherit" => Ok(std::process::Stdio::inherit()),
+    "piped" => Ok(std::process::Stdio::piped()),
+    "null" => Ok(std::process::Stdio::null()),
+    "pty" => pty.unwrap().slave_stdio(),
     _ => unreachable!(),
   }
 }
@@ -92,6 +124,79 @@ struct RunArgs {
   stdin_rid: u32,
   stdout_rid: u32,
   stderr_rid: u32,
+  clear_env: Option<bool>,
+  new_process_group: Option<bool>,
+  new_session: Option<bool>,
+  uid: Option<u32>,
+  gid: Option<u32>,
+}
+
+/// Applies the options that only exist on Unix. A child attached to a
+/// pseudo-terminal is started in a new session controlled by it.
+#[cfg(unix)]
+fn set_unix_options(
+  c: &mut Command,
+  run_args: &RunArgs,
+  pty: Option<&Pty>,
+) -> Result<(), ErrBox> {
+  use std::os::unix::process::CommandExt as UnixCommandExt;
+
+  if let Some(gid) = run_args.gid {
+    c.gid(gid);
+  }
+  if let Some(uid) = run_args.uid {
+    c.uid(uid);
+  }
+  // A new session is also a new process group, so the child can be killed
+  // together with all of its descendants.
+  if let Some(pty) = pty {
+    let slave = pty.slave.as_raw_fd();
+    unsafe {
+      c.pre_exec(move || set_controlling_terminal(slave));
+    }
+  } else if run_args.new_session.unwrap_or(false) {
+    unsafe {
+      c.pre_exec(|| {
+        if libc::setsid() == -1 {
+          return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+      });
+    }
+  } else if run_args.new_process_group.unwrap_or(false) {
+    unsafe {
+      c.pre_exec(|| {
+        if libc::setpgid(0, 0) == -1 {
+          return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+      });
+    }
+  }
+  Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_unix_options(
+  _c: &mut Command,
+  run_args: &RunArgs,
+  _pty: Option<&Pty>,
+) -> Result<(), ErrBox> {
+  if run_args.uid.is_some()
+    || run_args.gid.is_some()
+    || run_args.new_session.unwrap_or(false)
+    || run_args.new_process_group.unwrap_or(false)
+  {
+    return Err(
+      DenoError::new(
+        ErrorKind::Other,
+        "uid, gid, newSession and newProcessGroup are only supported on Unix"
+          .to_string(),
+      )
+      .into(),
+    );
+  }
+  Ok(())
 }
 
 struct ChildResource {
@@ -106,31 +211,43 @@ fn op_run(
   _zero_copy: Option<PinnedBuf>,
 ) -> Result<JsonOp, ErrBox> {
   let run_args: RunArgs = serde_json::from_value(args)?;
+  let args = &run_args.args;
+  let program = args.get(0).unwrap();
 
-  state.check_run()?;
+  let program = state.check_run_program(program)?;
   let state_ = state.clone();
 
-  let args = run_args.args;
-  let env = run_args.env;
-  let cwd = run_args.cwd;
+  let env = &run_args.env;
+  let cwd = run_args.cwd.clone();
 
-  let mut c = Command::new(args.get(0).unwrap());
+  // A whitelisted program is run from the file that was checked, even if
+  // `env` changes `$PATH`.
+  let mut c = Command::new(program);
   (1..args.len()).for_each(|i| {
     let arg = args.get(i).unwrap();
     c.arg(arg);
   });
   cwd.map(|d| c.current_dir(d));
-  for (key, value) in &env {
+  if run_args.clear_env.unwrap_or(false) {
+    c.env_clear();
+  }
+  for (key, value) in env {
     c.env(key, value);
   }
 
+  // One pseudo-terminal is shared by all stdio streams in "pty" mode.
+  let uses_pty = [&run_args.stdin, &run_args.stdout, &run_args.stderr]
+    .iter()
+    .any(|s| s.as_str() == "pty");
+  let pty = if uses_pty { Some(Pty::open()?) } else { None };
+
   // TODO: make this work with other resources, eg. sockets
   let stdin_rid = run_args.stdin_rid;
   if stdin_rid > 0 {
     let file = clone_file(stdin_rid, &state_)?;
     c.stdin(file);
   } else {
-    c.stdin(subprocess_stdio_map(run_args.stdin.as_ref()));
+    c.stdin(subprocess_stdio_map(run_args.stdin.as_ref(), pty.as_ref())?);
   }
 
   let stdout_rid = run_args.stdout_rid;
@@ -138,7 +255,10 @@ fn op_run(
     let file = clone_file(stdout_rid, &state_)?;
     c.stdout(file);
   } else {
-    c.stdout(subprocess_stdio_map(run_args.stdout.as_ref()));
+    c.stdout(subprocess_stdio_map(
+      run_args.stdout.as_ref(),
+      pty.as_ref(),
+    )?);
   }
 
   let stderr_rid = run_args.stderr_rid;
@@ -146,9 +266,14 @@ fn op_run(
     let file = clone_file(stderr_rid, &state_)?;
     c.stderr(file);
   } else {
-    c.stderr(subprocess_stdio_map(run_args.stderr.as_ref()));
+    c.stderr(subprocess_stdio_map(
+      run_args.stderr.as_ref(),
+      pty.as_ref(),
+    )?);
   }
 
+  set_unix_options(&mut c, &run_args, pty.as_ref())?;
+
   // Spawn the command.
   let mut child = c.spawn_async().map_err(ErrBox::from)?;
   let pid = child.id();
@@ -188,6 +313,20 @@ fn op_run(
     None => None,
   };
 
+  let pty_rid = match pty {
+    Some(pty) => {
+      #[cfg(unix)]
+      let fd = pty.master.as_raw_fd();
+      let master = tokio::fs::File::from_std(pty.into_master());
+      #[cfg(unix)]
+      let rid = table.add("pty", Box::new(StreamResource::Pty(master, fd)));
+      #[cfg(not(unix))]
+      let rid = table.add("pty", Box::new(StreamResource::FsFile(master)));
+      Some(rid)
+    }
+    None => None,
+  };
+
   let child_resource = ChildResource {
     child: futures::compat::Compat01As03::new(child),
   };
@@ -199,6 +338,7 @@ fn op_run(
     "stdinRid": stdin_rid,
     "stdoutRid": stdout_rid,
     "stderrRid": stderr_rid,
+    "ptyRid": pty_rid,
   })))
 }
 
@@ -235,8 +375,7 @@ fn op_run_status(
   let args: RunStatusArgs = serde_json::from_value(args)?;
   let rid = args.rid as u32;
 
-  state.check_run()?;
-
+  // Permission to run the child was checked when it was spawned.
   let future = ChildStatus {
     rid,
     state: state.clone(),
@@ -285,3 +424,27 @@ fn op_kill(
   kill(args.pid, args.signo)?;
   Ok(JsonOp::Sync(json!({})))
 }
+
+#[derive(Deserialize)]
+struct PtyResizeArgs {
+  rid: i32,
+  rows: u16,
+  columns: u16,
+}
+
+fn op_pty_resize(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: PtyResizeArgs = serde_json::from_value(args)?;
+  let table = state.lock_resource_table();
+  match table.get::<StreamResource>(args.rid as u32) {
+    #[cfg(unix)]
+    Some(StreamResource::Pty(_, fd)) => {
+      crate::pty::resize(*fd, args.rows, args.columns)?
+    }
+    _ => return Err(bad_resource()),
+  }
+  Ok(JsonOp::Sync(json!({})))
+}
@@ -1,16 +1,12 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{JsonOp, Value};
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::*;
 use rand::thread_rng;
 use rand::Rng;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op(
-    "get_random_values",
-    s.core_op(json_op(s.stateful_op(op_get_random_values))),
-  );
+  s.register_json_op(i, "get_random_values", op_get_random_values);
 }
 
 fn op_get_random_values(
@@ -1,7 +1,6 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{blocking_json, Deserialize, JsonOp, Value};
 use crate::deno_error::bad_resource;
-use crate::ops::json_op;
 use crate::repl;
 use crate::repl::Repl;
 use crate::state::ThreadSafeState;
@@ -11,14 +10,8 @@ use std::sync::Arc;
 use std::sync::Mutex;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op(
-    "repl_start",
-    s.core_op(json_op(s.stateful_op(op_repl_start))),
-  );
-  i.register_op(
-    "repl_readline",
-    s.core_op(json_op(s.stateful_op(op_repl_readline))),
-  );
+  s.register_json_op(i, "repl_start", op_repl_start);
+  s.register_json_op(i, "repl_readline", op_repl_readline);
 }
 
 struct ReplResource(Arc<Mutex<Repl>>);
@@ -1,11 +1,10 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{JsonOp, Value};
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::*;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("resources", s.core_op(json_op(s.stateful_op(op_resources))));
+  s.register_json_op(i, "resources", op_resources);
 }
 
 fn op_resources(
@@ -0,0 +1,171 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use super::dispatch_json::{Deserialize, JsonOp, Value};
+use crate::state::ThreadSafeState;
+use deno::*;
+
+#[cfg(unix)]
+use crate::deno_error::bad_resource;
+#[cfg(unix)]
+use futures::future::FutureExt;
+#[cfg(unix)]
+use futures::stream::StreamExt;
+#[cfg(unix)]
+use futures::task::AtomicWaker;
+#[cfg(unix)]
+use std::task::Poll;
+#[cfg(unix)]
+use tokio::prelude::stream::FlattenStream;
+#[cfg(unix)]
+use tokio_signal::unix::Signal;
+
+#[cfg(not(unix))]
+use crate::deno_error::{DenoError, ErrorKind};
+
+pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
+  s.register_json_op(i, "signal_bind", op_signal_bind);
+  s.register_json_op(i, "signal_unbind", op_signal_unbind);
+  s.register_json_op(i, "signal_poll", op_signal_poll);
+}
+
+#[cfg(unix)]
+type SignalFuture = tokio_signal::IoFuture<Signal>;
+
+/// Stream of deliveries of a signal. Dropping it lets a pending
+/// `op_signal_poll` resolve.
+#[cfg(unix)]
+struct SignalStreamResource {
+  stream: futures::compat::Compat01As03<FlattenStream<SignalFuture>>,
+  waker: AtomicWaker,
+}
+
+#[cfg(unix)]
+impl Resource for SignalStreamResource {}
+
+#[cfg(unix)]
+impl Drop for SignalStreamResource {
+  fn drop(&mut self) {
+    self.waker.wake();
+  }
+}
+
+#[derive(Deserialize)]
+struct BindSignalArgs {
+  signo: i32,
+}
+
+#[derive(Deserialize)]
+struct SignalArgs {
+  rid: i32,
+}
+
+/// Starts listening for the signal. Once a handler is installed the default
+/// action of the signal, like terminating the process, no longer happens.
+#[cfg(unix)]
+fn op_signal_bind(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: BindSignalArgs = serde_json::from_value(args)?;
+  let signal: SignalFuture = Signal::new(args.signo);
+  let stream = tokio::prelude::Future::flatten_stream(signal);
+  let mut table = state.lock_resource_table();
+  let rid = table.add(
+    "signal",
+    Box::new(SignalStreamResource {
+      stream: futures::compat::Compat01As03::new(stream),
+      waker: AtomicWaker::new(),
+    }),
+  );
+  Ok(JsonOp::Sync(json!({
+    "rid": rid,
+  })))
+}
+
+/// Resolves with `done: false` when the signal arrives, or with `done: true`
+/// once the stream has been unbound.
+#[cfg(unix)]
+fn op_signal_poll(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: SignalArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+  let state_ = state.clone();
+
+  let future = futures::future::poll_fn(move |cx| {
+    let mut table = state_.lock_resource_table();
+    match table.get_mut::<SignalStreamResource>(rid) {
+      Some(signal) => {
+        signal.waker.register(cx.waker());
+        signal.stream.poll_next_unpin(cx)
+      }
+      None => Poll::Ready(None),
+    }
+  })
+  .map(|result| -> Result<Value, ErrBox> {
+    match result {
+      Some(Err(err)) => Err(ErrBox::from(err)),
+      Some(Ok(_)) => Ok(json!({ "done": false })),
+      None => Ok(json!({ "done": true })),
+    }
+  });
+
+  Ok(JsonOp::Async(future.boxed()))
+}
+
+#[cfg(unix)]
+fn op_signal_unbind(
+  state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let args: SignalArgs = serde_json::from_value(args)?;
+  let rid = args.rid as u32;
+  let mut table = state.lock_resource_table();
+  table
+    .get::<SignalStreamResource>(rid)
+    .ok_or_else(bad_resource)?;
+  table.close(rid);
+  Ok(JsonOp::Sync(json!({})))
+}
+
+#[cfg(not(unix))]
+fn signals_not_supported() -> ErrBox {
+  DenoError::new(
+    ErrorKind::Other,
+    "Listening for signals is only supported on Unix".to_string(),
+  )
+  .into()
+}
+
+#[cfg(not(unix))]
+fn op_signal_bind(
+  _state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let _args: BindSignalArgs = serde_json::from_value(args)?;
+  Err(signals_not_supported())
+}
+
+#[cfg(not(unix))]
+fn op_signal_poll(
+  _state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let _args: SignalArgs = serde_json::from_value(args)?;
+  Err(signals_not_supported())
+}
+
+#[cfg(not(unix))]
+fn op_signal_unbind(
+  _state: &ThreadSafeState,
+  args: Value,
+  _zero_copy: Option<PinnedBuf>,
+) -> Result<JsonOp, ErrBox> {
+  let _args: SignalArgs = serde_json::from_value(args)?;
+  Err(signals_not_supported())
+}
@@ -1,6 +1,5 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use super::dispatch_json::{Deserialize, JsonOp, Value};
-use crate::ops::json_op;
 use crate::state::ThreadSafeState;
 use deno::*;
 use futures::future::FutureExt;
@@ -9,15 +8,9 @@ use std::time::Duration;
 use std::time::Instant;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op(
-    "global_timer_stop",
-    s.core_op(json_op(s.stateful_op(op_global_timer_stop))),
-  );
-  i.register_op(
-    "global_timer",
-    s.core_op(json_op(s.stateful_op(op_global_timer))),
-  );
-  i.register_op("now", s.core_op(json_op(s.stateful_op(op_now))));
+  s.register_json_op(i, "global_timer_stop", op_global_timer_stop);
+  s.register_json_op(i, "global_timer", op_global_timer);
+  s.register_json_op(i, "now", op_now);
 }
 
 fn op_global_timer_stop(
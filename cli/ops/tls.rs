@@ -4,7 +4,6 @@ use super::io::StreamResource;
 use crate::deno_error::bad_resource;
 use crate::deno_error::DenoError;
 use crate::deno_error::ErrorKind;
-use crate::ops::json_op;
 use crate::resolve_addr::resolve_addr;
 use crate::state::ThreadSafeState;
 use deno::Resource;
@@ -40,15 +39,9 @@ use webpki::DNSNameRef;
 use webpki_roots;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op("dial_tls", s.core_op(json_op(s.stateful_op(op_dial_tls))));
-  i.register_op(
-    "listen_tls",
-    s.core_op(json_op(s.stateful_op(op_listen_tls))),
-  );
-  i.register_op(
-    "accept_tls",
-    s.core_op(json_op(s.stateful_op(op_accept_tls))),
-  );
+  s.register_json_op(i, "dial_tls", op_dial_tls);
+  s.register_json_op(i, "listen_tls", op_listen_tls);
+  s.register_json_op(i, "accept_tls", op_accept_tls);
 }
 
 #[derive(Deserialize)]
@@ -68,8 +61,15 @@ pub fn op_dial_tls(
   let cert_file = args.cert_file;
   let state_ = state.clone();
   state.check_net(&args.hostname, args.port)?;
-  if let Some(path) = cert_file.clone() {
-    state.check_read(&path)?;
+  // A certificate file given with the call is in addition to the one given
+  // with `--cert`.
+  let mut ca_certs = vec![];
+  if let Some(path) = &state.global_state.ca_file {
+    ca_certs.extend(load_certs(path)?);
+  }
+  if let Some(path) = &cert_file {
+    state.check_read(path)?;
+    ca_certs.extend(load_certs(path)?);
   }
 
   let mut domain = args.hostname.clone();
@@ -88,18 +88,21 @@ pub fn op_dial_tls(
           Ok(v) => v,
           Err(e) => return futures::future::err(e),
         };
+        futures::future::ok((tcp_stream, local_addr, remote_addr))
+      })
+      .map_err(ErrBox::from)
+      .and_then(move |(tcp_stream, local_addr, remote_addr)| {
         let mut config = ClientConfig::new();
         config
           .root_store
           .add_server_trust_anchors(&webpki_roots::TLS_SERVER_ROOTS);
-
-        if let Some(path) = cert_file {
-          let key_file = match File::open(path) {
-            Ok(v) => v,
-            Err(e) => return futures::future::err(e),
-          };
-          let reader = &mut BufReader::new(key_file);
-          config.root_store.add_pem_file(reader).unwrap();
+        for cert in ca_certs {
+          if let Err(e) = config.root_store.add(&cert) {
+            return futures::future::err(ErrBox::from(DenoError::new(
+              ErrorKind::Other,
+              format!("Invalid certificate: {:?}", e),
+            )));
+          }
         }
         let tls_connector = TlsConnector::from(Arc::new(config));
         futures::future::ok((
@@ -109,7 +112,6 @@ pub fn op_dial_tls(
           remote_addr,
         ))
       })
-      .map_err(ErrBox::from)
       .and_then(
         move |(tls_connector, tcp_stream, local_addr, remote_addr)| {
           let dnsname = DNSNameRef::try_from_ascii_str(&domain)
@@ -137,7 +139,7 @@ pub fn op_dial_tls(
   Ok(JsonOp::Async(op.boxed()))
 }
 
-fn load_certs(path: &str) -> Result<Vec<Certificate>, ErrBox> {
+pub fn load_certs(path: &str) -> Result<Vec<Certificate>, ErrBox> {
   let cert_file = File::open(path)?;
   let reader = &mut BufReader::new(cert_file);
 
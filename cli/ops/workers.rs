@@ -4,7 +4,8 @@ use crate::deno_error::bad_resource;
 use crate::deno_error::js_check;
 use crate::deno_error::DenoError;
 use crate::deno_error::ErrorKind;
-use crate::ops::json_op;
+use crate::fs as deno_fs;
+use crate::permissions::{PermissionScope, WorkerPermissions};
 use crate::startup_data;
 use crate::state::ThreadSafeState;
 use crate::worker::Worker;
@@ -20,36 +21,19 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::Ordering;
 use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
 use std::task::Context;
 use std::task::Poll;
 
 pub fn init(i: &mut Isolate, s: &ThreadSafeState) {
-  i.register_op(
-    "create_worker",
-    s.core_op(json_op(s.stateful_op(op_create_worker))),
-  );
-  i.register_op(
-    "host_get_worker_closed",
-    s.core_op(json_op(s.stateful_op(op_host_get_worker_closed))),
-  );
-  i.register_op(
-    "host_post_message",
-    s.core_op(json_op(s.stateful_op(op_host_post_message))),
-  );
-  i.register_op(
-    "host_get_message",
-    s.core_op(json_op(s.stateful_op(op_host_get_message))),
-  );
+  s.register_json_op(i, "create_worker", op_create_worker);
+  s.register_json_op(i, "host_get_worker_closed", op_host_get_worker_closed);
+  s.register_json_op(i, "host_post_message", op_host_post_message);
+  s.register_json_op(i, "host_get_message", op_host_get_message);
   // TODO: make sure these two ops are only accessible to appropriate Worker
-  i.register_op(
-    "worker_post_message",
-    s.core_op(json_op(s.stateful_op(op_worker_post_message))),
-  );
-  i.register_op(
-    "worker_get_message",
-    s.core_op(json_op(s.stateful_op(op_worker_get_message))),
-  );
-  i.register_op("metrics", s.core_op(json_op(s.stateful_op(op_metrics))));
+  s.register_json_op(i, "worker_post_message", op_worker_post_message);
+  s.register_json_op(i, "worker_get_message", op_worker_get_message);
+  s.register_json_op(i, "metrics", op_metrics);
 }
 
 struct GetMessageFuture {
@@ -103,6 +87,66 @@ fn op_worker_post_message(
   Ok(JsonOp::Sync(json!({})))
 }
 
+/// A permission of a worker: `true` inherits it from the parent, `false`
+/// denies it and a list only grants the given paths or hosts.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PermissionArg {
+  Granted(bool),
+  Only(Vec<String>),
+}
+
+impl Default for PermissionArg {
+  fn default() -> Self {
+    PermissionArg::Granted(false)
+  }
+}
+
+impl PermissionArg {
+  fn into_scope(self, is_path: bool) -> Result<PermissionScope, ErrBox> {
+    Ok(match self {
+      PermissionArg::Granted(false) => PermissionScope::None,
+      PermissionArg::Granted(true) => PermissionScope::Inherit,
+      PermissionArg::Only(entries) if is_path => {
+        let mut paths = Vec::new();
+        for entry in entries {
+          let (_, path) = deno_fs::resolve_from_cwd(&entry)?;
+          paths.push(path);
+        }
+        PermissionScope::Only(paths)
+      }
+      PermissionArg::Only(entries) => PermissionScope::Only(entries),
+    })
+  }
+}
+
+/// Permissions of a new worker. Those left out are denied.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct WorkerPermissionsArgs {
+  read: PermissionArg,
+  write: PermissionArg,
+  net: PermissionArg,
+  env: PermissionArg,
+  run: PermissionArg,
+  plugin: PermissionArg,
+  hrtime: PermissionArg,
+}
+
+impl WorkerPermissionsArgs {
+  fn into_worker_permissions(self) -> Result<WorkerPermissions, ErrBox> {
+    Ok(WorkerPermissions {
+      read: self.read.into_scope(true)?,
+      write: self.write.into_scope(true)?,
+      net: self.net.into_scope(false)?,
+      env: self.env.into_scope(false)?,
+      run: self.run.into_scope(false)?,
+      plugin: self.plugin.into_scope(false)?,
+      hrtime: self.hrtime.into_scope(false)?,
+    })
+  }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CreateWorkerArgs {
@@ -110,6 +154,8 @@ struct CreateWorkerArgs {
   include_deno_namespace: bool,
   has_source_code: bool,
   source_code: String,
+  /// Without it the worker shares the permissions of its parent.
+  permissions: Option<WorkerPermissionsArgs>,
 }
 
 /// Create worker as the host
@@ -139,16 +185,30 @@ fn op_create_worker(
     }
   }
 
+  let permissions = match args.permissions {
+    // Narrowed from the current permissions of the parent. Later changes on
+    // either side don't affect the other.
+    Some(permissions) => {
+      let worker_permissions = permissions.into_worker_permissions()?;
+      let narrowed = parent_state
+        .permissions
+        .lock()
+        .unwrap()
+        .narrow(&worker_permissions)?;
+      Arc::new(Mutex::new(narrowed))
+    }
+    // By default share with parent.
+    None => parent_state.permissions.clone(),
+  };
+
   let (int, ext) = ThreadSafeState::create_channels();
   let child_state = ThreadSafeState::new(
     state.global_state.clone(),
-    Some(parent_state.permissions.clone()), // by default share with parent
+    Some(permissions),
     Some(module_specifier.clone()),
     include_deno_namespace,
     int,
   )?;
-  // TODO: add a new option to make child worker not sharing permissions
-  // with parent (aka .clone(), requests from child won't reflect in parent)
   let name = format!("USER-WORKER-{}", specifier);
   let deno_main_call = format!("denoMain({})", include_deno_namespace);
   let mut worker =
@@ -1,20 +1,30 @@
 // Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
 use crate::deno_error::{permission_denied_msg, type_error};
+use crate::flags::resolve_hosts;
 use crate::flags::DenoFlags;
+use crate::fs as deno_fs;
 use ansi_term::Style;
 #[cfg(not(test))]
 use atty;
 use deno::ErrBox;
 use log;
+use serde_derive::Deserialize;
 use std::collections::HashSet;
+use std::env;
 use std::fmt;
-#[cfg(not(test))]
+use std::fs::File;
+use std::fs::OpenOptions;
 use std::io;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 #[cfg(test)]
 use std::sync::atomic::AtomicBool;
 #[cfg(test)]
 use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 use url::Url;
 
 const PERMISSION_EMOJI: &str = "⚠️";
@@ -97,37 +107,260 @@ impl Default for PermissionState {
   }
 }
 
+/// How much of one permission a worker is given.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PermissionScope {
+  /// Denied, and cannot be requested either.
+  None,
+  /// The same state and whitelist as the parent.
+  Inherit,
+  /// Only the listed paths or hosts, all of which the parent must have.
+  Only(Vec<String>),
+}
+
+/// Permissions requested for a worker when it is created.
+#[derive(Clone, Debug)]
+pub struct WorkerPermissions {
+  pub read: PermissionScope,
+  pub write: PermissionScope,
+  pub net: PermissionScope,
+  pub env: PermissionScope,
+  pub run: PermissionScope,
+  pub plugin: PermissionScope,
+  pub hrtime: PermissionScope,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct DenoPermissions {
   // Keep in sync with cli/js/permissions.ts
   pub allow_read: PermissionState,
   pub read_whitelist: HashSet<String>,
+  pub read_blacklist: HashSet<String>,
   pub allow_write: PermissionState,
   pub write_whitelist: HashSet<String>,
+  pub write_blacklist: HashSet<String>,
   pub allow_net: PermissionState,
   pub net_whitelist: HashSet<String>,
+  pub net_blacklist: HashSet<String>,
   pub allow_env: PermissionState,
+  pub env_whitelist: HashSet<String>,
   pub allow_run: PermissionState,
+  pub run_whitelist: HashSet<String>,
   pub allow_plugin: PermissionState,
   pub allow_hrtime: PermissionState,
 }
 
+/// One permission of a policy file: `true` grants it, a list grants it for
+/// the listed paths, hosts, variables or programs only.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum PolicyEntry {
+  Granted(bool),
+  Only(Vec<String>),
+}
+
+/// Permissions read from the JSON file given with `--permissions`, e.g.
+/// `{ "read": ["./data"], "net": ["deno.land"], "denyRead": ["./data/keys"] }`.
+/// Relative paths are relative to the file. Programs given by name, without a
+/// path, are looked up on `$PATH`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct PermissionPolicy {
+  pub read: Option<PolicyEntry>,
+  pub write: Option<PolicyEntry>,
+  pub net: Option<PolicyEntry>,
+  pub env: Option<PolicyEntry>,
+  pub run: Option<PolicyEntry>,
+  pub plugin: bool,
+  pub hrtime: bool,
+  pub deny_read: Vec<String>,
+  pub deny_write: Vec<String>,
+  pub deny_net: Vec<String>,
+}
+
+impl PermissionPolicy {
+  pub fn load(filename: &str) -> Result<Self, ErrBox> {
+    let invalid = |err: String| {
+      io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Error reading permissions file \"{}\": {}", filename, err),
+      )
+    };
+    let json_string =
+      std::fs::read_to_string(filename).map_err(|e| invalid(e.to_string()))?;
+    let mut policy: PermissionPolicy =
+      serde_json::from_str(&json_string).map_err(|e| invalid(e.to_string()))?;
+
+    let base = env::current_dir()?.join(filename);
+    let base = base.parent().unwrap();
+    let resolve = |path: &mut String| -> Result<(), ErrBox> {
+      let joined = base.join(path.as_str());
+      let (_, resolved) = deno_fs::resolve_from_cwd(&joined.to_string_lossy())?;
+      *path = resolved;
+      Ok(())
+    };
+    if let Some(PolicyEntry::Only(paths)) = &mut policy.read {
+      paths.iter_mut().try_for_each(resolve)?;
+    }
+    if let Some(PolicyEntry::Only(paths)) = &mut policy.write {
+      paths.iter_mut().try_for_each(resolve)?;
+    }
+    if let Some(PolicyEntry::Only(programs)) = &mut policy.run {
+      programs
+        .iter_mut()
+        .filter(|program| Path::new(program.as_str()).components().count() > 1)
+        .try_for_each(resolve)?;
+    }
+    policy.deny_read.iter_mut().try_for_each(resolve)?;
+    policy.deny_write.iter_mut().try_for_each(resolve)?;
+    // Bare ports like ":8080" are expanded as with `--allow-net`.
+    if let Some(PolicyEntry::Only(hosts)) = &mut policy.net {
+      *hosts = resolve_hosts(std::mem::replace(hosts, Vec::new()));
+    }
+    policy.deny_net =
+      resolve_hosts(std::mem::replace(&mut policy.deny_net, Vec::new()));
+    Ok(policy)
+  }
+}
+
+/// Resolves whitelisted programs to the files they execute. This is done once,
+/// when the permissions are created, so that changing `$PATH` later does not
+/// change what is allowed. Programs that are not found are left out.
+fn resolve_programs<'a, I>(programs: I) -> HashSet<String>
+where
+  I: IntoIterator<Item = &'a String>,
+{
+  programs
+    .into_iter()
+    .filter_map(|program| resolve_program(program))
+    .map(|path| path.to_string_lossy().into_owned())
+    .collect()
+}
+
+/// Adds the permissions of a policy entry to the state and whitelist.
+fn merge_entry(
+  entry: &Option<PolicyEntry>,
+  state: &mut PermissionState,
+  whitelist: &mut HashSet<String>,
+) {
+  match entry {
+    Some(PolicyEntry::Granted(true)) => *state = PermissionState::Allow,
+    Some(PolicyEntry::Only(entries)) => whitelist.extend(entries.clone()),
+    Some(PolicyEntry::Granted(false)) | None => {}
+  }
+}
+
 impl DenoPermissions {
   pub fn from_flags(flags: &DenoFlags) -> Self {
     Self {
       allow_read: PermissionState::from(flags.allow_read),
       read_whitelist: flags.read_whitelist.iter().cloned().collect(),
+      read_blacklist: flags.read_blacklist.iter().cloned().collect(),
       allow_write: PermissionState::from(flags.allow_write),
       write_whitelist: flags.write_whitelist.iter().cloned().collect(),
+      write_blacklist: flags.write_blacklist.iter().cloned().collect(),
       allow_net: PermissionState::from(flags.allow_net),
       net_whitelist: flags.net_whitelist.iter().cloned().collect(),
+      net_blacklist: flags.net_blacklist.iter().cloned().collect(),
       allow_env: PermissionState::from(flags.allow_env),
+      env_whitelist: flags.env_whitelist.iter().cloned().collect(),
       allow_run: PermissionState::from(flags.allow_run),
+      run_whitelist: resolve_programs(&flags.run_whitelist),
       allow_plugin: PermissionState::from(flags.allow_plugin),
       allow_hrtime: PermissionState::from(flags.allow_hrtime),
     }
   }
 
+  /// Adds the permissions of a policy file to those given by flags.
+  pub fn merge_policy(&mut self, policy: &PermissionPolicy) {
+    merge_entry(&policy.read, &mut self.allow_read, &mut self.read_whitelist);
+    merge_entry(
+      &policy.write,
+      &mut self.allow_write,
+      &mut self.write_whitelist,
+    );
+    merge_entry(&policy.net, &mut self.allow_net, &mut self.net_whitelist);
+    merge_entry(&policy.env, &mut self.allow_env, &mut self.env_whitelist);
+    let mut run_whitelist = HashSet::new();
+    merge_entry(&policy.run, &mut self.allow_run, &mut run_whitelist);
+    self.run_whitelist.extend(resolve_programs(&run_whitelist));
+    if policy.plugin {
+      self.allow_plugin = PermissionState::Allow;
+    }
+    if policy.hrtime {
+      self.allow_hrtime = PermissionState::Allow;
+    }
+    self.read_blacklist.extend(policy.deny_read.iter().cloned());
+    self
+      .write_blacklist
+      .extend(policy.deny_write.iter().cloned());
+    self.net_blacklist.extend(policy.deny_net.iter().cloned());
+  }
+
+  /// Independent permissions for a worker, limited to `worker` and never
+  /// more than `self`. Fails if `worker` lists a path or host that `self`
+  /// has no access to. The blacklists of `self` always apply to the worker.
+  pub fn narrow(
+    &self,
+    worker: &WorkerPermissions,
+  ) -> Result<DenoPermissions, ErrBox> {
+    let (allow_read, read_whitelist) = narrow_whitelist(
+      "read",
+      &worker.read,
+      self.allow_read,
+      &self.read_whitelist,
+      |path| self.get_state_read(&Some(path)).is_allow(),
+    )?;
+    let (allow_write, write_whitelist) = narrow_whitelist(
+      "write",
+      &worker.write,
+      self.allow_write,
+      &self.write_whitelist,
+      |path| self.get_state_write(&Some(path)).is_allow(),
+    )?;
+    let (allow_net, net_whitelist) = narrow_whitelist(
+      "net",
+      &worker.net,
+      self.allow_net,
+      &self.net_whitelist,
+      |host| {
+        let (host, port) = split_host_and_port(host);
+        self.get_state_net(host, port).is_allow()
+      },
+    )?;
+    let (allow_env, env_whitelist) = narrow_whitelist(
+      "env",
+      &worker.env,
+      self.allow_env,
+      &self.env_whitelist,
+      |key| self.get_state_env(key).is_allow(),
+    )?;
+    let (allow_run, run_whitelist) = narrow_whitelist(
+      "run",
+      &worker.run,
+      self.allow_run,
+      &self.run_whitelist,
+      |program| self.get_state_run(program).is_allow(),
+    )?;
+    Ok(DenoPermissions {
+      allow_read,
+      read_whitelist,
+      read_blacklist: self.read_blacklist.clone(),
+      allow_write,
+      write_whitelist,
+      write_blacklist: self.write_blacklist.clone(),
+      allow_net,
+      net_whitelist,
+      net_blacklist: self.net_blacklist.clone(),
+      allow_env,
+      env_whitelist,
+      allow_run,
+      run_whitelist: resolve_programs(&run_whitelist),
+      allow_plugin: narrow_state("plugin", &worker.plugin, self.allow_plugin)?,
+      allow_hrtime: narrow_state("hrtime", &worker.hrtime, self.allow_hrtime)?,
+    })
+  }
+
   pub fn check_run(&self) -> Result<(), ErrBox> {
     self.allow_run.check(
       "access to run a subprocess",
@@ -135,8 +368,46 @@ impl DenoPermissions {
     )
   }
 
+  /// The whitelisted file that running `program` executes, if any.
+  fn whitelisted_program(&self, program: &str) -> Option<PathBuf> {
+    let path = resolve_program(program)?;
+    if self.run_whitelist.contains(path.to_str()?) {
+      Some(path)
+    } else {
+      None
+    }
+  }
+
+  fn get_state_run(&self, program: &str) -> PermissionState {
+    if self.whitelisted_program(program).is_some() {
+      return PermissionState::Allow;
+    }
+    self.allow_run
+  }
+
+  /// Checks that `program` may be run and returns what to execute. A
+  /// whitelisted program is returned as the file the whitelist entry resolved
+  /// to, so a whitelisted "git" does not allow running "./git" or another
+  /// "git" found on a changed `$PATH`.
+  pub fn check_run_program(&self, program: &str) -> Result<PathBuf, ErrBox> {
+    let whitelisted = self.whitelisted_program(program);
+    let state = if whitelisted.is_some() {
+      PermissionState::Allow
+    } else {
+      self.allow_run
+    };
+    state.check(
+      &format!("access to run \"{}\"", program),
+      "run again with the --allow-run flag",
+    )?;
+    Ok(whitelisted.unwrap_or_else(|| PathBuf::from(program)))
+  }
+
   fn get_state_read(&self, filename: &Option<&str>) -> PermissionState {
-    if check_path_white_list(filename, &self.read_whitelist) {
+    if check_path_list(filename, &self.read_blacklist) {
+      return PermissionState::Deny;
+    }
+    if check_path_list(filename, &self.read_whitelist) {
       return PermissionState::Allow;
     }
     self.allow_read
@@ -150,7 +421,10 @@ impl DenoPermissions {
   }
 
   fn get_state_write(&self, filename: &Option<&str>) -> PermissionState {
-    if check_path_white_list(filename, &self.write_whitelist) {
+    if check_path_list(filename, &self.write_blacklist) {
+      return PermissionState::Deny;
+    }
+    if check_path_list(filename, &self.write_whitelist) {
       return PermissionState::Allow;
     }
     self.allow_write
@@ -164,7 +438,10 @@ impl DenoPermissions {
   }
 
   fn get_state_net(&self, host: &str, port: Option<u16>) -> PermissionState {
-    if check_host_and_port_whitelist(host, port, &self.net_whitelist) {
+    if check_host_and_port_list(host, port, &self.net_blacklist) {
+      return PermissionState::Deny;
+    }
+    if check_host_and_port_list(host, port, &self.net_whitelist) {
       return PermissionState::Allow;
     }
     self.allow_net
@@ -209,6 +486,20 @@ impl DenoPermissions {
     )
   }
 
+  fn get_state_env(&self, key: &str) -> PermissionState {
+    if self.env_whitelist.contains(key) {
+      return PermissionState::Allow;
+    }
+    self.allow_env
+  }
+
+  pub fn check_env_var(&self, key: &str) -> Result<(), ErrBox> {
+    self.get_state_env(key).check(
+      &format!("access to environment variable \"{}\"", key),
+      "run again with the --allow-env flag",
+    )
+  }
+
   pub fn check_plugin(&self, filename: &str) -> Result<(), ErrBox> {
     self.allow_plugin.check(
       &format!("access to open a plugin: {}", filename),
@@ -223,7 +514,10 @@ impl DenoPermissions {
   }
 
   pub fn request_read(&mut self, path: &Option<&str>) -> PermissionState {
-    if check_path_white_list(path, &self.read_whitelist) {
+    if check_path_list(path, &self.read_blacklist) {
+      return PermissionState::Deny;
+    };
+    if check_path_list(path, &self.read_whitelist) {
       return PermissionState::Allow;
     };
     self.allow_read.request(&match path {
@@ -233,7 +527,10 @@ impl DenoPermissions {
   }
 
   pub fn request_write(&mut self, path: &Option<&str>) -> PermissionState {
-    if check_path_white_list(path, &self.write_whitelist) {
+    if check_path_list(path, &self.write_blacklist) {
+      return PermissionState::Deny;
+    };
+    if check_path_list(path, &self.write_whitelist) {
       return PermissionState::Allow;
     };
     self.allow_write.request(&match path {
@@ -304,13 +601,10 @@ fn permission_prompt(message: &str) -> bool {
   // print to stderr so that if deno is > to a file this is still displayed.
   eprint!("{}", Style::new().bold().paint(msg));
   loop {
-    let mut input = String::new();
-    let stdin = io::stdin();
-    let result = stdin.read_line(&mut input);
-    if result.is_err() {
-      return false;
+    let ch = match read_answer() {
+      Some(ch) => ch,
+      None => return false,
     };
-    let ch = input.chars().next().unwrap();
     match ch.to_ascii_lowercase() {
       'g' => return true,
       'd' => return false,
@@ -324,6 +618,29 @@ fn permission_prompt(message: &str) -> bool {
   }
 }
 
+/// Reads the answer to the permission prompt. On Unix a single key press is
+/// enough, without waiting for a new line.
+#[cfg(all(unix, not(test)))]
+fn read_answer() -> Option<char> {
+  let key = crate::tty::read_key().ok()?;
+  // Raw mode turns off echo and signals, so both are handled here.
+  if key == 3 || key == 4 {
+    eprintln!();
+    return None;
+  }
+  let ch = key as char;
+  eprintln!("{}", ch);
+  Some(ch)
+}
+
+#[cfg(all(not(unix), not(test)))]
+fn read_answer() -> Option<char> {
+  let mut input = String::new();
+  let stdin = io::stdin();
+  stdin.read_line(&mut input).ok()?;
+  input.chars().next()
+}
+
 #[cfg(test)]
 static STUB_PROMPT_VALUE: AtomicBool = AtomicBool::new(true);
 
@@ -350,16 +667,192 @@ fn log_perm_access(message: &str) {
   }
 }
 
-fn check_path_white_list(
-  filename: &Option<&str>,
-  white_list: &HashSet<String>,
-) -> bool {
+fn narrow_state(
+  name: &str,
+  scope: &PermissionScope,
+  parent: PermissionState,
+) -> Result<PermissionState, ErrBox> {
+  match scope {
+    PermissionScope::None => Ok(PermissionState::Deny),
+    PermissionScope::Inherit => Ok(parent),
+    PermissionScope::Only(_) => Err(type_error(format!(
+      "The {} permission of a worker cannot be limited to a list",
+      name
+    ))),
+  }
+}
+
+/// Narrows a permission with a whitelist. `parent_has` tells whether the
+/// parent has access to an entry.
+fn narrow_whitelist<F>(
+  name: &str,
+  scope: &PermissionScope,
+  parent: PermissionState,
+  parent_whitelist: &HashSet<String>,
+  parent_has: F,
+) -> Result<(PermissionState, HashSet<String>), ErrBox>
+where
+  F: Fn(&str) -> bool,
+{
+  match scope {
+    PermissionScope::None => Ok((PermissionState::Deny, HashSet::new())),
+    PermissionScope::Inherit => Ok((parent, parent_whitelist.clone())),
+    PermissionScope::Only(entries) => {
+      for entry in entries {
+        if !parent_has(entry) {
+          return Err(permission_denied_msg(format!(
+            "worker cannot be given {} access to \"{}\" without having it",
+            name, entry
+          )));
+        }
+      }
+      Ok((PermissionState::Deny, entries.iter().cloned().collect()))
+    }
+  }
+}
+
+/// Audit log written to the file given with `--permission-log`, one JSON
+/// object per line for every permission check and request.
+#[derive(Debug)]
+pub struct PermissionLog {
+  file: Mutex<File>,
+}
+
+impl PermissionLog {
+  pub fn open(filename: &str) -> Result<Self, ErrBox> {
+    let file = OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(filename)?;
+    Ok(Self {
+      file: Mutex::new(file),
+    })
+  }
+
+  /// Appends an entry. `result` is "granted", "denied" or "prompted", and
+  /// `op` is the op that asked for the permission, if one did.
+  pub fn write(
+    &self,
+    kind: &str,
+    target: Option<&str>,
+    result: &str,
+    op: Option<&str>,
+  ) {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_millis() as u64)
+      .unwrap_or(0);
+    let entry = json!({
+      "kind": kind,
+      "target": target,
+      "result": result,
+      "op": op,
+      "timestamp": timestamp,
+    });
+    let mut file = self.file.lock().unwrap();
+    if let Err(err) = writeln!(file, "{}", entry) {
+      debug!("Failed to write permission log: {}", err);
+    }
+  }
+}
+
+/// Finds the program that running `program` executes: the path itself if it
+/// has more than one component, otherwise the first match on `$PATH`.
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+  let path = Path::new(program);
+  if path.components().count() > 1 {
+    // Collecting the components drops any "." in the path.
+    return Some(env::current_dir().ok()?.join(path).components().collect());
+  }
+  let mut names = vec![program.to_string()];
+  if cfg!(windows) && path.extension().is_none() {
+    names.push(format!("{}.exe", program));
+  }
+  let dirs = env::var_os("PATH")?;
+  env::split_paths(&dirs)
+    .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+    .find(|candidate| candidate.is_file())
+}
+
+/// Splits "host:port" in its parts. The port is optional.
+fn split_host_and_port(s: &str) -> (&str, Option<u16>) {
+  if let Some(i) = s.rfind(':') {
+    if let Ok(port) = s[i + 1..].parse::<u16>() {
+      return (&s[..i], Some(port));
+    }
+  }
+  (s, None)
+}
+
+/// Matches `text` against a glob pattern. `*` matches any characters but
+/// `separator`, `**` matches any characters and `?` matches one character
+/// that is not `separator`. A pattern without wildcards matches itself.
+fn glob_match(pattern: &str, text: &str, separator: char) -> bool {
+  let pattern: Vec<char> = pattern.chars().collect();
+  let text: Vec<char> = text.chars().collect();
+  glob_match_chars(&pattern, &text, separator)
+}
+
+fn glob_match_chars(pattern: &[char], text: &[char], separator: char) -> bool {
+  match pattern.first() {
+    None => text.is_empty(),
+    Some('*') if pattern.get(1) == Some(&'*') => {
+      let rest = &pattern[2..];
+      // "a/**/b" also matches "a/b".
+      if rest.first() == Some(&separator)
+        && glob_match_chars(&rest[1..], text, separator)
+      {
+        return true;
+      }
+      (0..=text.len()).any(|i| glob_match_chars(rest, &text[i..], separator))
+    }
+    Some('*') => {
+      let rest = &pattern[1..];
+      for i in 0..=text.len() {
+        if glob_match_chars(rest, &text[i..], separator) {
+          return true;
+        }
+        if i < text.len() && text[i] == separator {
+          break;
+        }
+      }
+      false
+    }
+    Some('?') => {
+      !text.is_empty()
+        && text[0] != separator
+        && glob_match_chars(&pattern[1..], &text[1..], separator)
+    }
+    Some(c) => {
+      text.first() == Some(c)
+        && glob_match_chars(&pattern[1..], &text[1..], separator)
+    }
+  }
+}
+
+/// Whether a character has a special meaning in a glob pattern.
+pub fn is_glob_char(c: char) -> bool {
+  c == '*' || c == '?'
+}
+
+/// Whether a path or host entry is a pattern rather than a literal.
+pub fn is_glob(entry: &str) -> bool {
+  entry.contains(is_glob_char)
+}
+
+/// Whether an entry of `list` matches the path or one of its ancestors.
+fn check_path_list(filename: &Option<&str>, list: &HashSet<String>) -> bool {
   if filename.is_none() {
     return false;
   }
   let mut path_buf = PathBuf::from(filename.unwrap());
   loop {
-    if white_list.contains(path_buf.to_str().unwrap()) {
+    let path = path_buf.to_str().unwrap();
+    if list.contains(path)
+      || list.iter().any(|entry| {
+        is_glob(entry) && glob_match(entry, path, std::path::MAIN_SEPARATOR)
+      })
+    {
       return true;
     }
     if !path_buf.pop() {
@@ -369,14 +862,23 @@ fn check_path_white_list(
   false
 }
 
-fn check_host_and_port_whitelist(
+/// Whether an entry of `list` matches the host, or the host and port.
+fn check_host_and_port_list(
   host: &str,
   port: Option<u16>,
-  whitelist: &HashSet<String>,
+  list: &HashSet<String>,
 ) -> bool {
-  whitelist.contains(host)
-    || (port.is_some()
-      && whitelist.contains(&format!("{}:{}", host, port.unwrap())))
+  let host_and_port = port.map(|port| format!("{}:{}", host, port));
+  let matches = |target: &str| {
+    list.contains(target)
+      || list
+        .iter()
+        .any(|entry| is_glob(entry) && glob_match(entry, target, ':'))
+  };
+  matches(host)
+    || host_and_port
+      .as_ref()
+      .map_or(false, |s| matches(s.as_str()))
 }
 
 #[cfg(test)]
@@ -695,4 +1197,229 @@ mod tests {
     set_prompt_result(false);
     assert_eq!(perms1.request_hrtime(), PermissionState::Deny);
   }
+
+  #[test]
+  fn test_permissions_narrow() {
+    let parent = DenoPermissions::from_flags(&DenoFlags {
+      read_whitelist: svec!["/a"],
+      net_whitelist: svec!["example.com:443"],
+      allow_env: true,
+      ..Default::default()
+    });
+    let worker = WorkerPermissions {
+      read: PermissionScope::Only(svec!["/a/b"]),
+      write: PermissionScope::None,
+      net: PermissionScope::Inherit,
+      env: PermissionScope::Inherit,
+      run: PermissionScope::Inherit,
+      plugin: PermissionScope::None,
+      hrtime: PermissionScope::None,
+    };
+    let mut child = parent.narrow(&worker).unwrap();
+    assert!(child.check_read("/a/b/c").is_ok());
+    assert!(child.check_read("/a/c").is_err());
+    assert!(child.check_net("example.com", 443).is_ok());
+    assert!(child.check_env().is_ok());
+    assert_eq!(child.allow_run, PermissionState::Ask);
+    // Denied permissions can't be requested.
+    set_prompt_result(true);
+    assert_eq!(child.request_write(&None), PermissionState::Deny);
+    assert_eq!(child.request_hrtime(), PermissionState::Deny);
+
+    // No escalation beyond the parent.
+    let escalate = WorkerPermissions {
+      read: PermissionScope::Only(svec!["/b"]),
+      ..worker.clone()
+    };
+    assert!(parent.narrow(&escalate).is_err());
+    let escalate = WorkerPermissions {
+      net: PermissionScope::Only(svec!["example.com"]),
+      ..worker.clone()
+    };
+    assert!(parent.narrow(&escalate).is_err());
+    let only_env = WorkerPermissions {
+      env: PermissionScope::Only(svec!["HOME"]),
+      ..worker.clone()
+    };
+    let child = parent.narrow(&only_env).unwrap();
+    assert!(child.check_env_var("HOME").is_ok());
+    assert!(child.check_env_var("PATH").is_err());
+    let escalate = WorkerPermissions {
+      run: PermissionScope::Only(svec!["git"]),
+      ..worker.clone()
+    };
+    assert!(parent.narrow(&escalate).is_err());
+    let only_plugin = WorkerPermissions {
+      plugin: PermissionScope::Only(svec!["libtest.so"]),
+      ..worker
+    };
+    assert!(parent.narrow(&only_plugin).is_err());
+  }
+
+  #[test]
+  fn check_env_whitelist() {
+    let perms = DenoPermissions::from_flags(&DenoFlags {
+      env_whitelist: svec!["HOME"],
+      ..Default::default()
+    });
+    assert!(perms.check_env_var("HOME").is_ok());
+    assert!(perms.check_env_var("AWS_SECRET_ACCESS_KEY").is_err());
+    assert!(perms.check_env().is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn check_run_whitelist() {
+    let perms = DenoPermissions::from_flags(&DenoFlags {
+      run_whitelist: svec!["sh"],
+      ..Default::default()
+    });
+    let sh = resolve_program("sh").unwrap();
+    assert!(perms.run_whitelist.contains(sh.to_str().unwrap()));
+    assert_eq!(perms.check_run_program("sh").unwrap(), sh);
+    assert_eq!(perms.check_run_program(sh.to_str().unwrap()).unwrap(), sh);
+    assert!(perms.check_run_program("./sh").is_err());
+    assert!(perms.check_run_program("ls").is_err());
+    assert!(perms.check_run().is_err());
+  }
+
+  #[test]
+  fn check_blacklists() {
+    let perms = DenoPermissions::from_flags(&DenoFlags {
+      allow_read: true,
+      write_whitelist: svec!["/home/user"],
+      read_blacklist: svec!["/home/user/.ssh", "/srv/**/*.key"],
+      write_blacklist: svec!["/home/user/.ssh"],
+      allow_net: true,
+      net_blacklist: svec!["evil.com", "*.example.com:443"],
+      ..Default::default()
+    });
+
+    assert!(perms.check_read("/home/user/notes.txt").is_ok());
+    assert!(perms.check_read("/home/user/.ssh").is_err());
+    assert!(perms.check_read("/home/user/.ssh/id_rsa").is_err());
+    assert!(perms.check_read("/srv/server.key").is_err());
+    assert!(perms.check_read("/srv/a/b/server.key").is_err());
+    assert!(perms.check_read("/srv/a/b/server.crt").is_ok());
+
+    assert!(perms.check_write("/home/user/notes.txt").is_ok());
+    assert!(perms.check_write("/home/user/.ssh/id_rsa").is_err());
+
+    assert!(perms.check_net("evil.com", 80).is_err());
+    assert!(perms.check_net("api.example.com", 443).is_err());
+    assert!(perms.check_net("a.b.example.com", 443).is_err());
+    assert!(perms.check_net("api.example.com", 80).is_ok());
+    assert!(perms.check_net("example.com", 443).is_ok());
+  }
+
+  #[test]
+  fn check_glob_whitelists() {
+    let perms = DenoPermissions::from_flags(&DenoFlags {
+      read_whitelist: svec!["/srv/**/*.json", "/tmp/?.txt"],
+      net_whitelist: svec!["*.deno.land", "*.example.com:443"],
+      ..Default::default()
+    });
+
+    assert!(perms.check_read("/srv/config.json").is_ok());
+    assert!(perms.check_read("/srv/a/b/config.json").is_ok());
+    assert!(perms.check_read("/srv/a/b/config.yaml").is_err());
+    assert!(perms.check_read("/tmp/a.txt").is_ok());
+    assert!(perms.check_read("/tmp/ab.txt").is_err());
+
+    assert!(perms.check_net("std.deno.land", 80).is_ok());
+    assert!(perms.check_net("deno.land", 80).is_err());
+    assert!(perms.check_net("api.example.com", 443).is_ok());
+    assert!(perms.check_net("api.example.com", 80).is_err());
+  }
+
+  #[test]
+  fn test_glob_match() {
+    assert!(glob_match("/a/*/c", "/a/b/c", '/'));
+    assert!(!glob_match("/a/*/c", "/a/b/d/c", '/'));
+    assert!(glob_match("/a/**/c", "/a/b/d/c", '/'));
+    assert!(glob_match("/a/**/c", "/a/c", '/'));
+    assert!(glob_match("/a/**", "/a/b/c", '/'));
+    assert!(glob_match("*.com:443", "a.b.com:443", ':'));
+    assert!(!glob_match("*.com", "a.com:443", ':'));
+  }
+
+  #[test]
+  fn test_permission_policy() {
+    let temp_dir = tempfile::TempDir::new().expect("tempdir fail");
+    let filename = temp_dir.path().join("permissions.json");
+    std::fs::write(
+      &filename,
+      r#"{
+        "read": ["./data"],
+        "write": true,
+        "net": ["*.deno.land", ":4500"],
+        "env": ["HOME"],
+        "run": ["./tools/build.sh"],
+        "hrtime": true,
+        "denyRead": ["./data/keys", "./data/../secrets"],
+        "denyNet": [":8080"]
+      }"#,
+    )
+    .unwrap();
+    let filename = filename.to_str().unwrap();
+    let data = temp_dir.path().join("data");
+    let data = data.to_str().unwrap();
+    let secrets = temp_dir.path().join("secrets");
+    let build = temp_dir.path().join("tools/build.sh");
+
+    let mut perms = DenoPermissions::from_flags(&DenoFlags {
+      net_whitelist: svec!["localhost"],
+      ..Default::default()
+    });
+    perms.merge_policy(&PermissionPolicy::load(filename).unwrap());
+
+    assert!(perms.check_read(&format!("{}/file.txt", data)).is_ok());
+    assert!(perms.check_read(&format!("{}/keys/id_rsa", data)).is_err());
+    assert!(perms.check_read("/etc/passwd").is_err());
+    assert!(perms.check_write("/tmp/file.txt").is_ok());
+    assert!(perms.check_net("std.deno.land", 443).is_ok());
+    assert!(perms.check_net("localhost", 4545).is_ok());
+    assert!(perms.check_net("127.0.0.1", 4500).is_ok());
+    assert!(perms.check_net("localhost", 8080).is_err());
+    assert!(perms.check_net("example.com", 443).is_err());
+    assert!(perms.check_env_var("HOME").is_ok());
+    assert!(perms.check_env_var("PATH").is_err());
+    assert!(perms.read_blacklist.contains(secrets.to_str().unwrap()));
+    assert!(perms.check_run_program(build.to_str().unwrap()).is_ok());
+    assert!(perms.check_run().is_err());
+    assert_eq!(perms.allow_hrtime, PermissionState::Allow);
+    assert_eq!(perms.allow_plugin, PermissionState::Ask);
+
+    std::fs::write(filename, r#"{ "raed": true }"#).unwrap();
+    assert!(PermissionPolicy::load(filename).is_err());
+    std::fs::write(filename, r#"{ "plugin": ["x"] }"#).unwrap();
+    assert!(PermissionPolicy::load(filename).is_err());
+  }
+
+  #[test]
+  fn test_permission_log() {
+    let temp_dir = tempfile::TempDir::new().expect("tempdir fail");
+    let filename = temp_dir.path().join("permissions.log");
+    let filename = filename.to_str().unwrap();
+
+    let log = PermissionLog::open(filename).unwrap();
+    log.write("read", Some("/etc/passwd"), "denied", Some("open"));
+    log.write("env", None, "granted", None);
+    drop(log);
+
+    let contents = std::fs::read_to_string(filename).unwrap();
+    let entries: Vec<serde_json::Value> = contents
+      .lines()
+      .map(|line| serde_json::from_str(line).unwrap())
+      .collect();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["kind"], "read");
+    assert_eq!(entries[0]["target"], "/etc/passwd");
+    assert_eq!(entries[0]["result"], "denied");
+    assert_eq!(entries[0]["op"], "open");
+    assert!(entries[0]["timestamp"].is_u64());
+    assert_eq!(entries[1]["kind"], "env");
+    assert!(entries[1]["target"].is_null());
+    assert!(entries[1]["op"].is_null());
+  }
 }
@@ -0,0 +1,82 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+use deno::ErrBox;
+use nix::fcntl::{fcntl, FcntlArg, FdFlag};
+use nix::pty::{openpty, Winsize};
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::io::FromRawFd;
+use std::os::unix::io::RawFd;
+use std::process::Stdio;
+
+/// A pseudo-terminal allocated for a subprocess. The slave side becomes the
+/// stdio of the child, the master side is kept by Deno. Both sides are closed
+/// when dropped.
+pub struct Pty {
+  pub master: File,
+  pub slave: File,
+}
+
+impl Pty {
+  /// Allocates a pseudo-terminal of 80 columns and 24 rows. Neither side is
+  /// inherited by subprocesses unless passed explicitly.
+  pub fn open() -> Result<Self, ErrBox> {
+    let size = Winsize {
+      ws_row: 24,
+      ws_col: 80,
+      ws_xpixel: 0,
+      ws_ypixel: 0,
+    };
+    let r = openpty(Some(&size), None)?;
+    let pty = unsafe {
+      Self {
+        master: File::from_raw_fd(r.master),
+        slave: File::from_raw_fd(r.slave),
+      }
+    };
+    for file in &[&pty.master, &pty.slave] {
+      fcntl(file.as_raw_fd(), FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))?;
+    }
+    Ok(pty)
+  }
+
+  /// A new descriptor of the slave side, to be used as stdio of a child.
+  pub fn slave_stdio(&self) -> Result<Stdio, ErrBox> {
+    let fd = fcntl(self.slave.as_raw_fd(), FcntlArg::F_DUPFD_CLOEXEC(0))?;
+    Ok(unsafe { Stdio::from_raw_fd(fd) })
+  }
+
+  /// Closes the slave side once the child has been spawned, and returns the
+  /// master side.
+  pub fn into_master(self) -> File {
+    self.master
+  }
+}
+
+/// Makes the pseudo-terminal the controlling terminal of a new session. Only
+/// to be called in the child, between fork and exec.
+pub fn set_controlling_terminal(slave: RawFd) -> std::io::Result<()> {
+  unsafe {
+    if libc::setsid() == -1 {
+      return Err(std::io::Error::last_os_error());
+    }
+    if libc::ioctl(slave, libc::TIOCSCTTY as _, 0) == -1 {
+      return Err(std::io::Error::last_os_error());
+    }
+  }
+  Ok(())
+}
+
+/// Changes the size of the terminal, which sends SIGWINCH to its foreground
+/// process group.
+pub fn resize(master: RawFd, rows: u16, columns: u16) -> Result<(), ErrBox> {
+  let size = Winsize {
+    ws_row: rows,
+    ws_col: columns,
+    ws_xpixel: 0,
+    ws_ypixel: 0,
+  };
+  if unsafe { libc::ioctl(master, libc::TIOCSWINSZ as _, &size) } == -1 {
+    return Err(ErrBox::from(std::io::Error::last_os_error()));
+  }
+  Ok(())
+}
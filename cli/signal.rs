@@ -4,8 +4,12 @@ use deno::ErrBox;
 pub fn kill(pid: i32, signo: i32) -> Result<(), ErrBox> {
   use nix::sys::signal::{kill as unix_kill, Signal};
   use nix::unistd::Pid;
-  let sig = Signal::from_c_int(signo)?;
-  unix_kill(Pid::from_raw(pid), Option::Some(sig)).map_err(ErrBox::from)
+  // Signal 0 sends nothing, but still checks that the process exists.
+  let sig = match signo {
+    0 => None,
+    _ => Some(Signal::from_c_int(signo)?),
+  };
+  unix_kill(Pid::from_raw(pid), sig).map_err(ErrBox::from)
 }
 
 #[cfg(not(unix))]
@@ -4,6 +4,7 @@ use crate::global_state::ThreadSafeGlobalState;
 use crate::global_timer::GlobalTimer;
 use crate::import_map::ImportMap;
 use crate::metrics::Metrics;
+use crate::ops::json_op;
 use crate::ops::JsonOp;
 use crate::ops::MinimalOp;
 use crate::permissions::DenoPermissions;
@@ -12,6 +13,7 @@ use crate::worker::WorkerChannels;
 use deno::Buf;
 use deno::CoreOp;
 use deno::ErrBox;
+use deno::Isolate;
 use deno::Loader;
 use deno::ModuleSpecifier;
 use deno::Op;
@@ -26,6 +28,7 @@ use serde_json::Value;
 use std;
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::str;
 use std::sync::atomic::AtomicUsize;
@@ -58,6 +61,8 @@ pub struct State {
   pub seeded_rng: Option<Mutex<StdRng>>,
   pub include_deno_namespace: bool,
   pub resource_table: Mutex<ResourceTable>,
+  /// Name of the json op being dispatched, for the permission log.
+  pub current_op: Mutex<Option<&'static str>>,
 }
 
 impl Clone for ThreadSafeState {
@@ -133,8 +138,11 @@ impl ThreadSafeState {
   /// NOTE: This only works with JSON dispatcher.
   /// This is a band-aid for transition to `Isolate.register_op` API as most of our
   /// ops require `state` argument.
-  pub fn stateful_op<D>(
+  ///
+  /// `name` is the name the op is registered with, used by `--permission-log`.
+  fn stateful_op<D>(
     &self,
+    name: &'static str,
     dispatcher: D,
   ) -> impl Fn(Value, Option<PinnedBuf>) -> Result<JsonOp, ErrBox>
   where
@@ -143,9 +151,34 @@ impl ThreadSafeState {
     let state = self.clone();
 
     move |args: Value, zero_copy: Option<PinnedBuf>| -> Result<JsonOp, ErrBox> {
-      dispatcher(&state, args, zero_copy)
+      if state.global_state.permission_log.is_none() {
+        return dispatcher(&state, args, zero_copy);
+      }
+      *state.current_op.lock().unwrap() = Some(name);
+      let result = dispatcher(&state, args, zero_copy);
+      *state.current_op.lock().unwrap() = None;
+      result
     }
   }
+
+  /// Registers a JSON op that is given the `state` argument. The name it is
+  /// registered with is also the one recorded by `--permission-log`.
+  pub fn register_json_op<D>(
+    &self,
+    i: &Isolate,
+    name: &'static str,
+    dispatcher: D,
+  ) where
+    D: Fn(&ThreadSafeState, Value, Option<PinnedBuf>) -> Result<JsonOp, ErrBox>
+      + Send
+      + Sync
+      + 'static,
+  {
+    i.register_op(
+      name,
+      self.core_op(json_op(self.stateful_op(name, dispatcher))),
+    );
+  }
 }
 
 impl Loader for ThreadSafeState {
@@ -253,6 +286,7 @@ impl ThreadSafeState {
       seeded_rng,
       include_deno_namespace,
       resource_table: Mutex::new(ResourceTable::default()),
+      current_op: Mutex::new(None),
     };
 
     Ok(ThreadSafeState(Arc::new(state)))
@@ -265,39 +299,82 @@ impl ThreadSafeState {
     worker_id
   }
 
+  /// Records a permission check in the `--permission-log` file, along with
+  /// the op being dispatched.
+  pub fn log_permission_check<T>(
+    &self,
+    kind: &str,
+    target: Option<&str>,
+    result: &Result<T, ErrBox>,
+  ) {
+    let op = *self.current_op.lock().unwrap();
+    self
+      .global_state
+      .log_permission_check(kind, target, result, op);
+  }
+
   #[inline]
   pub fn check_read(&self, filename: &str) -> Result<(), ErrBox> {
-    self.permissions.lock().unwrap().check_read(filename)
+    let result = self.permissions.lock().unwrap().check_read(filename);
+    self.log_permission_check("read", Some(filename), &result);
+    result
   }
 
   #[inline]
   pub fn check_write(&self, filename: &str) -> Result<(), ErrBox> {
-    self.permissions.lock().unwrap().check_write(filename)
+    let result = self.permissions.lock().unwrap().check_write(filename);
+    self.log_permission_check("write", Some(filename), &result);
+    result
   }
 
   #[inline]
   pub fn check_env(&self) -> Result<(), ErrBox> {
-    self.permissions.lock().unwrap().check_env()
+    let result = self.permissions.lock().unwrap().check_env();
+    self.log_permission_check("env", None, &result);
+    result
+  }
+
+  #[inline]
+  pub fn check_env_var(&self, key: &str) -> Result<(), ErrBox> {
+    let result = self.permissions.lock().unwrap().check_env_var(key);
+    self.log_permission_check("env", Some(key), &result);
+    result
   }
 
   #[inline]
   pub fn check_net(&self, hostname: &str, port: u16) -> Result<(), ErrBox> {
-    self.permissions.lock().unwrap().check_net(hostname, port)
+    let result = self.permissions.lock().unwrap().check_net(hostname, port);
+    let target = format!("{}:{}", hostname, port);
+    self.log_permission_check("net", Some(&target), &result);
+    result
   }
 
   #[inline]
   pub fn check_net_url(&self, url: &url::Url) -> Result<(), ErrBox> {
-    self.permissions.lock().unwrap().check_net_url(url)
+    let result = self.permissions.lock().unwrap().check_net_url(url);
+    self.log_permission_check("net", Some(url.as_str()), &result);
+    result
   }
 
   #[inline]
   pub fn check_run(&self) -> Result<(), ErrBox> {
-    self.permissions.lock().unwrap().check_run()
+    let result = self.permissions.lock().unwrap().check_run();
+    self.log_permission_check("run", None, &result);
+    result
+  }
+
+  #[inline]
+  pub fn check_run_program(&self, program: &str) -> Result<PathBuf, ErrBox> {
+    let result = self.permissions.lock().unwrap().check_run_program(program);
+    self.log_permission_check("run", Some(program), &result);
+    result
   }
 
   #[inline]
   pub fn check_plugin(&self, filename: &str) -> Result<(), ErrBox> {
-    self.permissions.lock().unwrap().check_plugin(filename)
+    let result = self.permissions.lock().unwrap().check_plugin(filename);
+    self.log_permission_check("plugin", Some(filename), &result);
+    result
   }
 
   pub fn check_dyn_import(
@@ -0,0 +1,16 @@
+try {
+  new Worker("./053_worker_permissions/worker.ts", {
+    permissions: { write: ["/"] }
+  });
+} catch (e) {
+  console.log("escalation: " + e.name);
+}
+
+const worker = new Worker("./053_worker_permissions/worker.ts", {
+  permissions: { read: ["./053_worker_permissions"] }
+});
+worker.onmessage = (msg): void => {
+  console.log(msg.data);
+  Deno.exit(0);
+};
+worker.postMessage("START");
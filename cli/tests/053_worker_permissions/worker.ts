@@ -0,0 +1,23 @@
+function attempt(name: string, fn: () => void): string {
+  try {
+    fn();
+    return name + ": ok";
+  } catch (e) {
+    return name + ": " + e.name;
+  }
+}
+
+onmessage = (): void => {
+  const results = [
+    attempt("read inside", (): void => {
+      Deno.readFileSync("053_worker_permissions/worker.ts");
+    }),
+    attempt("read outside", (): void => {
+      Deno.readFileSync("hello.txt");
+    }),
+    attempt("env", (): void => {
+      Deno.env();
+    })
+  ];
+  postMessage(results.join("\n"));
+};
@@ -96,6 +96,137 @@ fn bundle_exports() {
   assert_eq!(output.stderr, b"");
 }
 
+#[test]
+fn vendor_then_run_offline() {
+  use tempfile::TempDir;
+
+  let g = util::http_server();
+  let t = TempDir::new().expect("tempdir fail");
+  let vendor_dir = t.path().join("vendor");
+  let status = util::deno_cmd()
+    .current_dir(util::root_path())
+    .arg("vendor")
+    .arg("http://localhost:4545/cli/tests/003_relative_import.ts")
+    .arg(&vendor_dir)
+    .spawn()
+    .expect("failed to spawn script")
+    .wait()
+    .expect("failed to wait for the child process");
+  assert!(status.success());
+  drop(g);
+
+  let import_map = vendor_dir.join("import_map.json");
+  assert!(import_map.is_file());
+  assert!(vendor_dir
+    .join("http/localhost_PORT4545/cli/tests/subdir/print_hello.ts")
+    .is_file());
+
+  // The remote module is only reachable through the import map.
+  let test = t.path().join("test.ts");
+  std::fs::write(
+    &test,
+    "import \"http://localhost:4545/cli/tests/003_relative_import.ts\";",
+  )
+  .expect("error writing file");
+  let output = util::deno_cmd()
+    .current_dir(util::root_path())
+    .arg("run")
+    .arg("--no-remote")
+    .arg(format!("--importmap={}", import_map.display()))
+    .arg(&test)
+    .output()
+    .expect("failed to spawn script");
+  assert_eq!(std::str::from_utf8(&output.stdout).unwrap().trim(), "Hello");
+  assert_eq!(output.stderr, b"");
+}
+
+#[test]
+fn run_watch_restarts_on_change() {
+  use std::io::BufRead;
+  use std::process::Stdio;
+  use tempfile::TempDir;
+
+  let t = TempDir::new().expect("tempdir fail");
+  let main = t.path().join("main.js");
+  let dep = t.path().join("dep.js");
+  std::fs::write(
+    &main,
+    "import { message } from \"./dep.js\"; console.log(message);",
+  )
+  .expect("error writing file");
+  std::fs::write(&dep, "export const message = \"first\";")
+    .expect("error writing file");
+
+  let mut deno = util::deno_cmd()
+    .current_dir(util::root_path())
+    .arg("run")
+    .arg("--watch")
+    .arg(&main)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null())
+    .spawn()
+    .expect("failed to spawn script");
+  let mut stdout = std::io::BufReader::new(deno.stdout.take().unwrap()).lines();
+  assert_eq!(stdout.next().unwrap().unwrap(), "first");
+
+  // Changing an imported module runs the main module again.
+  std::fs::write(&dep, "export const message = \"second\";")
+    .expect("error writing file");
+  assert_eq!(stdout.next().unwrap().unwrap(), "second");
+
+  deno.kill().expect("failed to kill the child process");
+  deno.wait().expect("failed to wait for the child process");
+}
+
+#[test]
+fn run_permission_log() {
+  use std::process::Stdio;
+  use tempfile::TempDir;
+
+  let t = TempDir::new().expect("tempdir fail");
+  let test = t.path().join("test.js");
+  let log = t.path().join("permissions.log");
+  std::fs::write(
+    &test,
+    "Deno.statSync(\"cli/tests/fixture.json\");
+      try { Deno.env(); } catch (e) {}",
+  )
+  .expect("error writing file");
+  let status = util::deno_cmd()
+    .current_dir(util::root_path())
+    .arg("run")
+    .arg("--allow-read")
+    .arg(format!("--permission-log={}", log.display()))
+    .arg(&test)
+    .stdin(Stdio::null())
+    .spawn()
+    .expect("failed to spawn script")
+    .wait()
+    .expect("failed to wait for the child process");
+  assert!(status.success());
+
+  let entries: Vec<serde_json::Value> = std::fs::read_to_string(&log)
+    .expect("error reading the permission log")
+    .lines()
+    .map(|line| serde_json::from_str(line).expect("invalid log entry"))
+    .collect();
+  let stat = entries
+    .iter()
+    .find(|entry| entry["op"] == "stat")
+    .expect("no entry for stat");
+  assert_eq!(stat["kind"], "read");
+  assert_eq!(stat["result"], "granted");
+  assert!(stat["target"].as_str().unwrap().ends_with("fixture.json"));
+  assert!(stat["timestamp"].is_u64());
+  let env = entries
+    .iter()
+    .find(|entry| entry["op"] == "env")
+    .expect("no entry for env");
+  assert_eq!(env["kind"], "env");
+  assert_eq!(env["result"], "denied");
+  assert!(env["target"].is_null());
+}
+
 // TODO(#2933): Rewrite this test in rust.
 #[test]
 fn repl_test() {
@@ -406,6 +537,11 @@ itest!(_052_no_remote_flag {
   http_server: true,
 });
 
+itest!(_053_worker_permissions {
+  args: "run --reload --allow-read --allow-env 053_worker_permissions.ts",
+  output: "053_worker_permissions.ts.out",
+});
+
 itest!(lock_check_ok {
   args: "run --lock=lock_check_ok.json http://127.0.0.1:4545/cli/tests/003_relative_import.ts",
   output: "003_relative_import.ts.out",
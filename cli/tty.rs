@@ -0,0 +1,55 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+//! Terminal modes and sizes, used by the `set_raw` and `console_size` ops and
+//! by the permission prompt.
+use deno::ErrBox;
+use nix::sys::termios;
+use std::collections::HashMap;
+use std::io::Read;
+use std::os::unix::io::RawFd;
+use std::sync::Mutex;
+
+lazy_static! {
+  /// Modes of the terminals that were put in raw mode, restored when raw mode
+  /// is left.
+  static ref ORIGINAL_MODES: Mutex<HashMap<RawFd, termios::Termios>> =
+    Mutex::new(HashMap::new());
+}
+
+/// Puts the terminal in raw mode, where input is available byte by byte, is
+/// not echoed and special characters like ctrl-c are not processed. Leaving
+/// raw mode restores the mode the terminal had before.
+pub fn set_raw(fd: RawFd, raw: bool) -> Result<(), ErrBox> {
+  let mut original_modes = ORIGINAL_MODES.lock().unwrap();
+  if raw {
+    if original_modes.contains_key(&fd) {
+      return Ok(());
+    }
+    let original = termios::tcgetattr(fd)?;
+    let mut mode = original.clone();
+    termios::cfmakeraw(&mut mode);
+    termios::tcsetattr(fd, termios::SetArg::TCSADRAIN, &mode)?;
+    original_modes.insert(fd, original);
+  } else if let Some(original) = original_modes.remove(&fd) {
+    termios::tcsetattr(fd, termios::SetArg::TCSADRAIN, &original)?;
+  }
+  Ok(())
+}
+
+/// Returns the columns and rows of the terminal.
+pub fn console_size(fd: RawFd) -> Result<(u16, u16), ErrBox> {
+  let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+  if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ as _, &mut size) } == -1 {
+    return Err(ErrBox::from(std::io::Error::last_os_error()));
+  }
+  Ok((size.ws_col, size.ws_row))
+}
+
+/// Reads a single key press from stdin, without waiting for a new line.
+pub fn read_key() -> Result<u8, ErrBox> {
+  set_raw(libc::STDIN_FILENO, true)?;
+  let mut buf = [0u8; 1];
+  let result = std::io::stdin().read_exact(&mut buf);
+  set_raw(libc::STDIN_FILENO, false)?;
+  result?;
+  Ok(buf[0])
+}
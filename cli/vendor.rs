@@ -0,0 +1,88 @@
+// Copyright 2018-2019 the Deno authors. All rights reserved. MIT license.
+//! Implementation of `deno vendor`, which copies the remote modules of a
+//! module graph into a local directory together with an import map that
+//! redirects their URLs to the copies.
+use crate::disk_cache::DiskCache;
+use crate::file_fetcher::map_file_extension;
+use crate::global_state::ThreadSafeGlobalState;
+use crate::msg;
+use deno::ErrBox;
+use deno::ModuleSpecifier;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use url::Url;
+
+static IMPORT_MAP_FILENAME: &str = "import_map.json";
+
+/// Writes the remote modules among `modules` to `out_dir`, using the layout
+/// of the remote modules cache, and returns the path of the import map.
+///
+/// Relative imports between vendored modules keep working because the
+/// layout mirrors the URL paths, absolute ones are covered by the import map.
+pub async fn vendor(
+  global_state: ThreadSafeGlobalState,
+  modules: Vec<Url>,
+  out_dir: PathBuf,
+) -> Result<PathBuf, ErrBox> {
+  let vendor_cache = DiskCache::new(&out_dir);
+  let mut imports = BTreeMap::new();
+
+  for url in modules {
+    if url.scheme() != "http" && url.scheme() != "https" {
+      continue;
+    }
+    let module_specifier = ModuleSpecifier::from(url.clone());
+    let source_file = global_state
+      .file_fetcher
+      .fetch_source_file_async(&module_specifier, None)
+      .await?;
+
+    let filename = vendor_cache.get_cache_filename(&source_file.url);
+    // Local files are typed by their extension only, the content type the
+    // module was served with is lost.
+    if map_file_extension(&filename) != source_file.media_type {
+      eprintln!(
+        "Warning: {} was served as {} and may not load from {}",
+        source_file.url,
+        msg::enum_name_media_type(source_file.media_type),
+        filename.display()
+      );
+    }
+    vendor_cache.set(&filename, &source_file.source_code)?;
+
+    let target = format!("./{}", to_url_path(&filename));
+    imports.insert(source_file.url.to_string(), target.clone());
+    imports.insert(url.to_string(), target);
+  }
+
+  let import_map = json!({ "imports": imports });
+  let import_map_str = serde_json::to_string_pretty(&import_map)?;
+  vendor_cache
+    .set(Path::new(IMPORT_MAP_FILENAME), import_map_str.as_bytes())?;
+  Ok(out_dir.join(IMPORT_MAP_FILENAME))
+}
+
+/// Joins the components of a relative path with forward slashes.
+fn to_url_path(filename: &Path) -> String {
+  let components: Vec<String> = filename
+    .components()
+    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+    .collect();
+  components.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_to_url_path() {
+    let cache = DiskCache::new(&PathBuf::from("vendor"));
+    let url = Url::parse("http://localhost:4545/std/mod.ts").unwrap();
+    assert_eq!(
+      to_url_path(&cache.get_cache_filename(&url)),
+      "http/localhost_PORT4545/std/mod.ts"
+    );
+  }
+}
@@ -57,6 +57,8 @@ impl Worker {
       ops::fetch::init(&mut i, &state);
       ops::files::init(&mut i, &state);
       ops::fs::init(&mut i, &state);
+      ops::fs_events::init(&mut i, &state);
+      ops::http::init(&mut i, &state);
       ops::io::init(&mut i, &state);
       ops::plugins::init(&mut i, &state, op_registry);
       ops::net::init(&mut i, &state);
@@ -67,6 +69,7 @@ impl Worker {
       ops::random::init(&mut i, &state);
       ops::repl::init(&mut i, &state);
       ops::resources::init(&mut i, &state);
+      ops::signal::init(&mut i, &state);
       ops::timers::init(&mut i, &state);
       ops::workers::init(&mut i, &state);
 
@@ -23,7 +23,8 @@ ARGS:
   EXE_NAME  Name for executable
   SCRIPT_URL  Local or remote URL of script to install
   [FLAGS...]  List of flags for script, both Deno permission and script specific
-              flag can be used.
+              flag can be used. A --permissions=<FILE> policy file is
+              referenced by its absolute path.
 
 OPTIONS:
   -d, --dir <PATH> Installation directory path (defaults to ~/.deno/bin)
@@ -239,11 +240,16 @@ export async function install(
   }
 
   const grantedPermissions: Permission[] = [];
+  const policyFlags: string[] = [];
   const scriptArgs: string[] = [];
 
   for (const flag of flags) {
     const permission = getPermissionFromFlag(flag);
-    if (permission === undefined) {
+    if (flag.startsWith("--permissions=")) {
+      // The executable can be run from any directory.
+      const policyFile = flag.slice("--permissions=".length);
+      policyFlags.push(`--permissions=${path.resolve(policyFile)}`);
+    } else if (permission === undefined) {
       scriptArgs.push(flag);
     } else {
       grantedPermissions.push(permission);
@@ -254,6 +260,7 @@ export async function install(
     "deno",
     "run",
     ...grantedPermissions.map(getFlagFromPermission),
+    ...policyFlags,
     moduleUrl,
     ...scriptArgs
   ];
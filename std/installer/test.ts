@@ -273,6 +273,27 @@ exit $ret
   );
 });
 
+installerTest(async function installWithPermissionsFile(): Promise<void> {
+  await install(
+    "echo_test",
+    "http://localhost:4500/installer/testdata/echo.ts",
+    ["--allow-net", "--permissions=permissions.json", "--foobar"]
+  );
+
+  const { HOME } = env();
+  const filePath = path.resolve(HOME, ".deno/bin/echo_test");
+  const policyPath = path.resolve("permissions.json");
+
+  // The policy file is referenced by its absolute path.
+  const contents = await fs.readFileStr(filePath);
+  assert(
+    contents.includes(
+      `"run" "--allow-net" "--permissions=${policyPath}" ` +
+        `"http://localhost:4500/installer/testdata/echo.ts" "--foobar"`
+    )
+  );
+});
+
 installerTest(async function installLocalModuleAndRun(): Promise<void> {
   const tempDir = await makeTempDir();
   const localModule = path.join(Deno.cwd(), "installer", "testdata", "echo.ts");
@@ -0,0 +1,10 @@
+// Used for benchmarking Deno's native HTTP server. See tools/http_benchmark.py
+const addr = Deno.args[1] || "127.0.0.1:4500";
+const [hostname, port] = addr.split(":");
+const server = Deno.serveHttp(Deno.listen({ hostname, port: Number(port) }));
+const body = new TextEncoder().encode("Hello World\n");
+
+console.log("Listening on", addr);
+for await (const req of server) {
+  req.respond({ body });
+}
@@ -63,6 +63,16 @@ def deno_http(deno_exe):
     return run(deno_cmd, port)
 
 
+def deno_http_native(deno_exe):
+    port = get_port()
+    deno_cmd = [
+        deno_exe, "run", "--allow-net", "tools/deno_http_native.ts",
+        server_addr(port)
+    ]
+    print "http_benchmark testing DENO using Deno.serveHttp."
+    return run(deno_cmd, port)
+
+
 def deno_tcp_proxy(deno_exe, hyper_hello_exe):
     port = get_port()
     origin_port = get_port()
@@ -157,6 +167,7 @@ def http_benchmark(build_dir):
         "deno_tcp_current_thread": deno_tcp_current_thread(deno_exe),
         # "deno_http" was once called "deno_net_http"
         "deno_http": deno_http(deno_exe),
+        "deno_http_native": deno_http_native(deno_exe),
         "deno_proxy": deno_http_proxy(deno_exe, hyper_hello_exe),
         "deno_proxy_tcp": deno_tcp_proxy(deno_exe, hyper_hello_exe),
         "deno_core_single": deno_core_single(core_http_bench_exe),
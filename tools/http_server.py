@@ -52,6 +52,32 @@ class ContentTypeHandler(QuietSimpleHTTPRequestHandler):
                       '\r\n--boundary--\r\n'
                       'Epilogue'))
             return
+        if "etag_script.ts" in self.path:
+            self.protocol_version = 'HTTP/1.1'
+            if self.headers.getheader('if-none-match') == '33a64df551425fcc55e':
+                self.send_response(304, 'Not Modified')
+                self.send_header('Content-type', 'application/typescript')
+                self.send_header('ETag', '33a64df551425fcc55e')
+                self.end_headers()
+            else:
+                body = 'console.log("etag");\n'
+                self.send_response(200, 'OK')
+                self.send_header('Content-type', 'application/typescript')
+                self.send_header('Content-length', str(len(body)))
+                self.send_header('ETag', '33a64df551425fcc55e')
+                self.end_headers()
+                self.wfile.write(bytes(body))
+            return
+        if "auth_echo.ts" in self.path:
+            # Responds with the Authorization header of the request, if any.
+            self.protocol_version = 'HTTP/1.1'
+            body = self.headers.getheader('authorization') or ''
+            self.send_response(200, 'OK')
+            self.send_header('Content-type', 'application/typescript')
+            self.send_header('Content-length', str(len(body)))
+            self.end_headers()
+            self.wfile.write(bytes(body))
+            return
         return SimpleHTTPServer.SimpleHTTPRequestHandler.do_GET(self)
 
     def do_POST(self):